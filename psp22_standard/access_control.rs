@@ -1,24 +1,31 @@
-use crate::PSP22Error;
+use crate::{PSP22Error, PSP22Event};
 use ink::{
+    prelude::{vec, vec::Vec},
     primitives::AccountId,
     storage::Mapping,
 };
 
 pub type RoleType = u32;
 pub const DEFAULT_ADMIN_ROLE: RoleType = 0;
+pub const TRANSFER_MANAGER_ROLE: RoleType = ink::selector_id!("TRANSFER_MANAGER");
+pub const VESTING_MANAGER_ROLE: RoleType = ink::selector_id!("VESTING_MANAGER");
+pub const MINTER_ROLE: RoleType = ink::selector_id!("MINTER");
+pub const BURNER_ROLE: RoleType = ink::selector_id!("BURNER");
 
 #[ink::storage_item]
 #[derive(Debug, Default)]
 pub struct AccessControlData {
     admin_roles: Mapping<RoleType, RoleType>,
     members: Mapping<(RoleType, Option<AccountId>), ()>,
+    minter_allowances: Mapping<AccountId, u128>,
 }
 
 impl AccessControlData {
     pub fn new() -> AccessControlData {
         let data = AccessControlData {
             admin_roles: Default::default(),
-            members: Default::default()
+            members: Default::default(),
+            minter_allowances: Default::default(),
         };
         data
     }
@@ -53,4 +60,37 @@ impl AccessControlData {
         self.members.remove(&(role, account));
         Ok(())
     }
+
+    pub fn minter_allowance(&self, minter: AccountId) -> u128 {
+        self.minter_allowances.get(minter).unwrap_or_default()
+    }
+
+    /// Grants the `MINTER_ROLE` to `minter`, if needed, and sets the amount it can mint.
+    pub fn configure_minter(&mut self, minter: AccountId, allowance: u128) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if !self.has_role(MINTER_ROLE, Some(minter)) {
+            self.grant_role(MINTER_ROLE, Some(minter))?;
+        }
+        self.minter_allowances.insert(minter, &allowance);
+        Ok(vec![PSP22Event::MinterConfigured { minter, allowance }])
+    }
+
+    /// Revokes the `MINTER_ROLE` of `minter` together with its allowance.
+    pub fn remove_minter(&mut self, minter: AccountId) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.revoke_role(MINTER_ROLE, Some(minter))?;
+        self.minter_allowances.remove(minter);
+        Ok(vec![PSP22Event::MinterRemoved { minter }])
+    }
+
+    /// Decreases the allowance of `minter` by the `value` it mints.
+    pub fn spend_minter_allowance(&mut self, minter: AccountId, value: u128) -> Result<(), PSP22Error> {
+        if !self.has_role(MINTER_ROLE, Some(minter)) {
+            return Err(PSP22Error::MissingRole)
+        }
+        let allowance = self.minter_allowance(minter);
+        if allowance < value {
+            return Err(PSP22Error::MinterAllowanceExceeded)
+        }
+        self.minter_allowances.insert(minter, &(allowance - value));
+        Ok(())
+    }
 }
@@ -6,6 +6,12 @@ use ink::{
     storage::Mapping,
 };
 
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+/// Maximum number of vesting schedules which can lock an account's balance at once.
+pub const MAX_VESTING_SCHEDULES: u32 = 16;
+
 pub enum PSP22Event {
     Transfer {
         from: Option<AccountId>,
@@ -17,6 +23,43 @@ pub enum PSP22Event {
         spender: AccountId,
         amount: u128,
     },
+    MinterConfigured {
+        minter: AccountId,
+        allowance: u128,
+    },
+    MinterRemoved {
+        minter: AccountId,
+    },
+}
+
+/// Locks `amount` of an account's balance, releasing it linearly from `start` to
+/// `start + duration`. Nothing is released before `cliff`, so a schedule with
+/// `cliff == start` and no `duration` unlocks everything at once.
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct VestingSchedule {
+    pub amount: u128,
+    /// Timestamps (in milliseconds).
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl VestingSchedule {
+    /// Returns the part of `amount` which is unlocked at `timestamp`.
+    pub fn vested_amount(&self, timestamp: u64) -> u128 {
+        if timestamp < self.cliff {
+            return 0;
+        }
+        let elapsed = timestamp.saturating_sub(self.start);
+        if elapsed >= self.duration {
+            return self.amount;
+        }
+        // Split `amount` so that `amount * elapsed / duration` can not overflow.
+        let duration = self.duration as u128;
+        let elapsed = elapsed as u128;
+        (self.amount / duration) * elapsed + (self.amount % duration) * elapsed / duration
+    }
 }
 
 #[ink::storage_item]
@@ -25,7 +68,10 @@ pub struct PSP22Data {
     total_supply: u128,
     balances: Mapping<AccountId, u128>,
     allowances: Mapping<(AccountId, AccountId), u128>,
-    cap: u128
+    cap: u128,
+    soulbound: bool,
+    transferable_accounts: Mapping<AccountId, ()>,
+    vesting_schedules: Mapping<AccountId, Vec<VestingSchedule>>,
 }
 
 impl PSP22Data {
@@ -35,11 +81,117 @@ impl PSP22Data {
             total_supply: Default::default(),
             balances: Default::default(),
             allowances: Default::default(),
-            cap: cap
+            cap: cap,
+            soulbound: false,
+            transferable_accounts: Default::default(),
+            vesting_schedules: Default::default(),
         };
         data
     }
 
+    /// Creates a soulbound token. Balances can only be minted and burned, or moved
+    /// to and from the accounts added with `add_transferable_account`.
+    pub fn new_soulbound(cap: u128) -> PSP22Data {
+        let mut data = Self::new(cap);
+        data.soulbound = true;
+        data
+    }
+
+    pub fn is_soulbound(&self) -> bool {
+        self.soulbound
+    }
+
+    pub fn is_transferable_account(&self, account: AccountId) -> bool {
+        self.transferable_accounts.contains(account)
+    }
+
+    /// Allows `account` to send and receive tokens in soulbound mode.
+    pub fn add_transferable_account(&mut self, account: AccountId) {
+        self.transferable_accounts.insert(account, &());
+    }
+
+    pub fn remove_transferable_account(&mut self, account: AccountId) {
+        self.transferable_accounts.remove(account);
+    }
+
+    /// In soulbound mode, tokens can only move if either side is a transferable account.
+    fn ensure_transferable(&self, from: AccountId, to: AccountId) -> Result<(), PSP22Error> {
+        if self.soulbound
+            && !self.is_transferable_account(from)
+            && !self.is_transferable_account(to)
+        {
+            return Err(PSP22Error::NonTransferable);
+        }
+        Ok(())
+    }
+
+    /// In soulbound mode, allowances can only be granted to transferable accounts.
+    /// Lowering an allowance is always possible, so holders can revoke spenders
+    /// removed from the transferable accounts.
+    fn ensure_spender(&self, spender: AccountId) -> Result<(), PSP22Error> {
+        if self.soulbound && !self.is_transferable_account(spender) {
+            return Err(PSP22Error::NonTransferable);
+        }
+        Ok(())
+    }
+
+    pub fn schedules_of(&self, account: AccountId) -> Vec<VestingSchedule> {
+        self.vesting_schedules.get(account).unwrap_or_default()
+    }
+
+    /// Returns the amount released by all vesting schedules of `account` at `timestamp`.
+    pub fn vested_amount(&self, account: AccountId, timestamp: u64) -> u128 {
+        self.schedules_of(account)
+            .iter()
+            .fold(0, |total, schedule| total.saturating_add(schedule.vested_amount(timestamp)))
+    }
+
+    /// Returns the part of `account` balance which can not be spent at `timestamp`.
+    pub fn locked_balance_of(&self, account: AccountId, timestamp: u64) -> u128 {
+        self.schedules_of(account).iter().fold(0, |total, schedule| {
+            total.saturating_add(schedule.amount.saturating_sub(schedule.vested_amount(timestamp)))
+        })
+    }
+
+    /// Locks part of the unlocked balance of `account` under a new vesting `schedule`.
+    /// Schedules which are fully vested at `now` are dropped.
+    pub fn add_vesting_schedule(
+        &mut self,
+        account: AccountId,
+        schedule: VestingSchedule,
+        now: u64,
+    ) -> Result<(), PSP22Error> {
+        if schedule.amount == 0 || schedule.cliff < schedule.start {
+            return Err(PSP22Error::Custom(String::from("Invalid vesting schedule.")));
+        }
+        let mut schedules = self.schedules_of(account);
+        schedules.retain(|s| s.vested_amount(now) < s.amount);
+        if schedules.len() >= MAX_VESTING_SCHEDULES as usize {
+            return Err(PSP22Error::Custom(String::from("Too many vesting schedules.")));
+        }
+        let locked = self.locked_balance_of(account, now);
+        if self.balance_of(account).saturating_sub(locked) < schedule.amount {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+        schedules.push(schedule);
+        self.vesting_schedules.insert(account, &schedules);
+        Ok(())
+    }
+
+    /// Fails if spending `value` at `now` would use the locked part of `account` balance.
+    fn ensure_unlocked(&self, account: AccountId, value: u128, now: u64) -> Result<(), PSP22Error> {
+        if !self.vesting_schedules.contains(account) {
+            return Ok(());
+        }
+        let unlocked = self
+            .balance_of(account)
+            .saturating_sub(self.locked_balance_of(account, now));
+        if unlocked < value {
+            return Err(PSP22Error::TokensLocked);
+        }
+        Ok(())
+    }
+
     pub fn cap(&self) -> u128 {
         self.cap
     }
@@ -56,13 +208,15 @@ impl PSP22Data {
         self.allowances.get((owner, spender)).unwrap_or_default()
     }
 
-    /// Transfers `value` tokens from `caller` to `to`.
+    /// Transfers `value` tokens from `caller` to `to` at `now`.
     pub fn transfer(
         &mut self,
         caller: AccountId,
         to: AccountId,
         value: u128,
+        now: u64,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.ensure_transferable(caller, to)?;
         if caller == to || value == 0 {
             return Ok(vec![]);
         }
@@ -70,6 +224,7 @@ impl PSP22Data {
         if from_balance < value {
             return Err(PSP22Error::InsufficientBalance);
         }
+        self.ensure_unlocked(caller, value, now)?;
 
         if from_balance == value {
             self.balances.remove(caller);
@@ -96,12 +251,14 @@ impl PSP22Data {
         from: AccountId,
         to: AccountId,
         value: u128,
+        now: u64,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.ensure_transferable(from, to)?;
         if from == to || value == 0 {
             return Ok(vec![]);
         }
         if caller == from {
-            return self.transfer(caller, to, value);
+            return self.transfer(caller, to, value, now);
         }
 
         let allowance = self.allowance(from, caller);
@@ -112,6 +269,7 @@ impl PSP22Data {
         if from_balance < value {
             return Err(PSP22Error::InsufficientBalance);
         }
+        self.ensure_unlocked(from, value, now)?;
 
         if allowance == value {
             self.allowances.remove((from, caller));
@@ -152,6 +310,9 @@ impl PSP22Data {
         spender: AccountId,
         value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if value > self.allowance(owner, spender) {
+            self.ensure_spender(spender)?;
+        }
         if owner == spender {
             return Ok(vec![]);
         }
@@ -174,6 +335,7 @@ impl PSP22Data {
         spender: AccountId,
         delta_value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.ensure_spender(spender)?;
         if owner == spender || delta_value == 0 {
             return Ok(vec![]);
         }
@@ -238,8 +400,13 @@ impl PSP22Data {
         }])
     }
 
-    /// Burns `value` tokens from `from` account.
-    pub fn burn(&mut self, from: AccountId, value: u128) -> Result<Vec<PSP22Event>, PSP22Error> {
+    /// Burns `value` tokens from `from` account at `now`.
+    pub fn burn(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        now: u64,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
         if value == 0 {
             return Ok(vec![]);
         }
@@ -247,6 +414,7 @@ impl PSP22Data {
         if balance < value {
             return Err(PSP22Error::InsufficientBalance);
         }
+        self.ensure_unlocked(from, value, now)?;
         if balance == value {
             self.balances.remove(from);
         } else {
@@ -16,5 +16,8 @@ pub enum PSP22Error {
     MissingRole,
     InvalidCaller,
     WithdrawFeeError,
-    NotEnoughBalance
+    NotEnoughBalance,
+    NonTransferable,
+    TokensLocked,
+    MinterAllowanceExceeded,
 }
\ No newline at end of file
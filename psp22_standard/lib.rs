@@ -7,15 +7,15 @@ mod traits;
 mod ownable;
 mod access_control;
 
-pub use data::{PSP22Data, PSP22Event};
+pub use data::{PSP22Data, PSP22Event, VestingSchedule, MAX_VESTING_SCHEDULES};
 pub use ownable::OwnableData;
-pub use access_control::{AccessControlData, RoleType, DEFAULT_ADMIN_ROLE};
+pub use access_control::{AccessControlData, RoleType, DEFAULT_ADMIN_ROLE, TRANSFER_MANAGER_ROLE, VESTING_MANAGER_ROLE, MINTER_ROLE, BURNER_ROLE};
 pub use errors::PSP22Error;
-pub use traits::{PSP22Burnable, PSP22Metadata, PSP22Mintable, PSP22Capped, UpgradeableTrait, Ownable, AccessControl, AdminTrait, PSP22};
+pub use traits::{PSP22Burnable, PSP22Metadata, PSP22Mintable, PSP22Capped, UpgradeableTrait, Ownable, AccessControl, AdminTrait, PSP22, PSP22Soulbound, PSP22Vesting, PSP22Minters};
 
 #[cfg(feature = "contract")]
 #[ink::contract]
-mod token {
+pub mod token {
     use crate::{
         PSP22Data,  
         PSP22Error, 
@@ -32,7 +32,15 @@ mod token {
         AccessControlData,
         DEFAULT_ADMIN_ROLE,
         RoleType,
-        PSP22
+        TRANSFER_MANAGER_ROLE,
+        VESTING_MANAGER_ROLE,
+        MINTER_ROLE,
+        BURNER_ROLE,
+        VestingSchedule,
+        PSP22,
+        PSP22Soulbound,
+        PSP22Vesting,
+        PSP22Minters
     };
     use ink::prelude::{string::String, vec::Vec};
 
@@ -64,6 +72,25 @@ mod token {
             }
         }
 
+        /// Creates a token whose balances can not be transferred, except to and from
+        /// the accounts allowed by the `TRANSFER_MANAGER_ROLE`.
+        #[ink(constructor)]
+        pub fn new_soulbound(
+            cap: u128,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
+            Self {
+                data: PSP22Data::new_soulbound(cap),
+                ownable_data: OwnableData::new(Some(Self::env().caller())),
+                access_control_data: AccessControlData::new(),
+                name,
+                symbol,
+                decimals,
+            }
+        }
+
         fn emit_events(&self, events: Vec<PSP22Event>) {
             for event in events {
                 match event {
@@ -79,6 +106,12 @@ mod token {
                         spender,
                         amount,
                     }),
+                    PSP22Event::MinterConfigured { minter, allowance } => {
+                        self.env().emit_event(MinterConfigured { minter, allowance })
+                    }
+                    PSP22Event::MinterRemoved { minter } => {
+                        self.env().emit_event(MinterRemoved { minter })
+                    }
                 }
             }
         }
@@ -102,6 +135,19 @@ mod token {
         value: u128,
     }
 
+    #[ink(event)]
+    pub struct MinterConfigured {
+        #[ink(topic)]
+        minter: AccountId,
+        allowance: u128,
+    }
+
+    #[ink(event)]
+    pub struct MinterRemoved {
+        #[ink(topic)]
+        minter: AccountId,
+    }
+
     impl PSP22 for Token {
         #[ink(message)]
         fn total_supply(&self) -> u128 {
@@ -125,7 +171,9 @@ mod token {
             value: u128,
             _data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
-            let events = self.data.transfer(self.env().caller(), to, value)?;
+            let events = self
+                .data
+                .transfer(self.env().caller(), to, value, self.env().block_timestamp())?;
             self.emit_events(events);
             Ok(())
         }
@@ -140,7 +188,7 @@ mod token {
         ) -> Result<(), PSP22Error> {
             let events = self
                 .data
-                .transfer_from(self.env().caller(), from, to, value)?;
+                .transfer_from(self.env().caller(), from, to, value, self.env().block_timestamp())?;
             self.emit_events(events);
             Ok(())
         }
@@ -197,16 +245,49 @@ mod token {
     impl PSP22Mintable for Token {
         #[ink(message)]
         fn mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            self.access_control_data
+                .spend_minter_allowance(self.env().caller(), value)?;
             let events = self.data.mint(to, value)?;
             self.emit_events(events);
             Ok(())
         }
     }
 
+    impl PSP22Minters for Token {
+        #[ink(message)]
+        fn minter_allowance(&self, minter: AccountId) -> u128 {
+            self.access_control_data.minter_allowance(minter)
+        }
+
+        #[ink(message)]
+        fn configure_minter(&mut self, minter: AccountId, allowance: u128) -> Result<(), PSP22Error> {
+            if !self.access_control_data.only_role(self.get_role_admin(MINTER_ROLE), Some(self.env().caller())) {
+                return Err(PSP22Error::MissingRole)
+            }
+            let events = self.access_control_data.configure_minter(minter, allowance)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn remove_minter(&mut self, minter: AccountId) -> Result<(), PSP22Error> {
+            if !self.access_control_data.only_role(self.get_role_admin(MINTER_ROLE), Some(self.env().caller())) {
+                return Err(PSP22Error::MissingRole)
+            }
+            let events = self.access_control_data.remove_minter(minter)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
     impl PSP22Burnable for Token {
         #[ink(message)]
         fn burn(&mut self, from: AccountId, value: u128) -> Result<(), PSP22Error> {
-            let events = self.data.burn(from, value)?;
+            let caller = self.env().caller();
+            if caller != from && !self.access_control_data.has_role(BURNER_ROLE, Some(caller)) {
+                return Err(PSP22Error::MissingRole)
+            }
+            let events = self.data.burn(from, value, self.env().block_timestamp())?;
             self.emit_events(events);
             Ok(())
         }
@@ -219,6 +300,62 @@ mod token {
         }
     }
 
+    impl PSP22Soulbound for Token {
+        #[ink(message)]
+        fn is_soulbound(&self) -> bool {
+            self.data.is_soulbound()
+        }
+
+        #[ink(message)]
+        fn is_transferable_account(&self, account: AccountId) -> bool {
+            self.data.is_transferable_account(account)
+        }
+
+        #[ink(message)]
+        fn add_transferable_account(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            if !self.access_control_data.only_role(TRANSFER_MANAGER_ROLE, Some(self.env().caller())) {
+                return Err(PSP22Error::MissingRole)
+            }
+            self.data.add_transferable_account(account);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn remove_transferable_account(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            if !self.access_control_data.only_role(TRANSFER_MANAGER_ROLE, Some(self.env().caller())) {
+                return Err(PSP22Error::MissingRole)
+            }
+            self.data.remove_transferable_account(account);
+            Ok(())
+        }
+    }
+
+    impl PSP22Vesting for Token {
+        #[ink(message)]
+        fn schedules_of(&self, account: AccountId) -> Vec<VestingSchedule> {
+            self.data.schedules_of(account)
+        }
+
+        #[ink(message)]
+        fn vested_amount(&self, account: AccountId, timestamp: u64) -> u128 {
+            self.data.vested_amount(account, timestamp)
+        }
+
+        #[ink(message)]
+        fn locked_balance_of(&self, account: AccountId) -> u128 {
+            self.data.locked_balance_of(account, self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn add_vesting_schedule(&mut self, account: AccountId, schedule: VestingSchedule) -> Result<(), PSP22Error> {
+            if !self.access_control_data.only_role(VESTING_MANAGER_ROLE, Some(self.env().caller())) {
+                return Err(PSP22Error::MissingRole)
+            }
+            self.data
+                .add_vesting_schedule(account, schedule, self.env().block_timestamp())
+        }
+    }
+
     impl UpgradeableTrait for Token {
         #[ink(message)]
         fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), PSP22Error> {
@@ -325,6 +462,272 @@ mod token {
 
     #[cfg(test)]
     mod tests {
-        crate::tests!(Token, (|supply| Token::new(supply, None, None, 0)));
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        type Event = <Token as ink::reflect::ContractEventBase>::Type;
+        type Constructor = fn(u128, Option<String>, Option<String>, u8) -> Token;
+
+        const CAP: u128 = 1000;
+
+        // Creates a token capped at `cap` with `constructor`. The caller becomes
+        // its admin and a minter of the whole cap, and gets `supply` tokens.
+        fn setup(constructor: Constructor, cap: u128, supply: u128) -> Token {
+            let caller = ink::env::caller::<E>();
+            let mut token = constructor(cap, None, None, 0);
+            assert!(token.init_admin_role().is_ok());
+            assert!(token.configure_minter(caller, cap).is_ok());
+            assert!(token.mint(caller, supply).is_ok());
+            token
+        }
+
+        fn decode_events(shift: usize) -> Vec<Event> {
+            recorded_events()
+                .skip(shift)
+                .map(|e| <Event as scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .collect()
+        }
+
+        // The PSP22 suite is expanded in a child module of this one.
+        crate::tests!(Token, (|supply| super::setup(Token::new, supply, supply)));
+
+        #[ink::test]
+        fn soulbound_blocks_transfers_and_allowances() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new_soulbound, CAP, CAP);
+            assert!(token.is_soulbound());
+            let start = recorded_events().count();
+
+            assert_eq!(token.transfer(acc.bob, 10, vec![]), Err(PSP22Error::NonTransferable));
+            assert_eq!(token.approve(acc.bob, 10), Err(PSP22Error::NonTransferable));
+            assert_eq!(token.increase_allowance(acc.bob, 10), Err(PSP22Error::NonTransferable));
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.transfer_from(acc.alice, acc.bob, 10, vec![]),
+                Err(PSP22Error::NonTransferable)
+            );
+            assert_eq!(token.balance_of(acc.alice), CAP);
+            assert_eq!(decode_events(start).len(), 0);
+
+            // Lowering an allowance is always possible.
+            set_caller::<E>(acc.alice);
+            assert_eq!(token.approve(acc.bob, 0), Ok(()));
+            assert_eq!(
+                token.decrease_allowance(acc.bob, 10),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+        }
+
+        #[ink::test]
+        fn soulbound_mint_and_burn_work() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new_soulbound, CAP, 0);
+            let start = recorded_events().count();
+
+            assert_eq!(token.mint(acc.bob, 100), Ok(()));
+            // Only the holder or a burner can burn tokens.
+            assert_eq!(token.burn(acc.bob, 40), Err(PSP22Error::MissingRole));
+            assert_eq!(token.grant_role(BURNER_ROLE, Some(acc.alice)), Ok(()));
+            assert_eq!(token.burn(acc.bob, 30), Ok(()));
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.burn(acc.bob, 10), Ok(()));
+
+            assert_eq!(token.balance_of(acc.bob), 60);
+            assert_eq!(token.total_supply(), 60);
+            assert_eq!(decode_events(start).len(), 3);
+        }
+
+        #[ink::test]
+        fn soulbound_transferable_account_works() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new_soulbound, CAP, 0);
+            assert_eq!(token.mint(acc.bob, 100), Ok(()));
+
+            // Only the transfer manager can configure the allowlist.
+            assert_eq!(token.add_transferable_account(acc.charlie), Err(PSP22Error::MissingRole));
+            assert_eq!(token.grant_role(TRANSFER_MANAGER_ROLE, Some(acc.alice)), Ok(()));
+            assert_eq!(token.add_transferable_account(acc.charlie), Ok(()));
+            assert!(token.is_transferable_account(acc.charlie));
+
+            // Bob can redeem to Charlie, directly or through an allowance.
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.transfer(acc.charlie, 30, vec![]), Ok(()));
+            assert_eq!(token.approve(acc.charlie, 20), Ok(()));
+            set_caller::<E>(acc.charlie);
+            assert_eq!(token.transfer_from(acc.bob, acc.charlie, 20, vec![]), Ok(()));
+            assert_eq!(token.balance_of(acc.bob), 50);
+            assert_eq!(token.balance_of(acc.charlie), 50);
+
+            // Charlie can send tokens back, but Bob still can not send to Django.
+            assert_eq!(token.transfer(acc.bob, 10, vec![]), Ok(()));
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.transfer(acc.django, 10, vec![]), Err(PSP22Error::NonTransferable));
+
+            // Bob can still revoke Charlie once removed from the allowlist.
+            assert_eq!(token.approve(acc.charlie, 20), Ok(()));
+            set_caller::<E>(acc.alice);
+            assert_eq!(token.remove_transferable_account(acc.charlie), Ok(()));
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.transfer(acc.charlie, 10, vec![]), Err(PSP22Error::NonTransferable));
+            assert_eq!(
+                token.increase_allowance(acc.charlie, 10),
+                Err(PSP22Error::NonTransferable)
+            );
+            assert_eq!(token.decrease_allowance(acc.charlie, 5), Ok(()));
+            assert_eq!(token.approve(acc.charlie, 0), Ok(()));
+            assert_eq!(token.allowance(acc.bob, acc.charlie), 0);
+        }
+
+        #[ink::test]
+        fn add_vesting_schedule_requires_role_and_unlocked_balance() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new, CAP, CAP);
+            let schedule = VestingSchedule {
+                amount: 600,
+                start: 1000,
+                cliff: 1000,
+                duration: 1000,
+            };
+
+            assert_eq!(
+                token.add_vesting_schedule(acc.alice, schedule.clone()),
+                Err(PSP22Error::MissingRole)
+            );
+            assert_eq!(token.grant_role(VESTING_MANAGER_ROLE, Some(acc.alice)), Ok(()));
+            assert_eq!(token.add_vesting_schedule(acc.alice, schedule.clone()), Ok(()));
+            assert_eq!(token.schedules_of(acc.alice), vec![schedule.clone()]);
+
+            // Only 400 tokens are left to lock.
+            assert_eq!(
+                token.add_vesting_schedule(acc.alice, schedule),
+                Err(PSP22Error::InsufficientBalance)
+            );
+        }
+
+        #[ink::test]
+        fn linear_vesting_unlocks_balance_over_time() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new, CAP, CAP);
+            assert_eq!(token.grant_role(VESTING_MANAGER_ROLE, Some(acc.alice)), Ok(()));
+            assert_eq!(token.transfer(acc.bob, 500, vec![]), Ok(()));
+            let schedule = VestingSchedule {
+                amount: 400,
+                start: 1000,
+                cliff: 1200,
+                duration: 1000,
+            };
+            assert_eq!(token.add_vesting_schedule(acc.bob, schedule), Ok(()));
+
+            // Before the cliff only the unscheduled part can be spent.
+            set_block_timestamp::<E>(1100);
+            assert_eq!(token.vested_amount(acc.bob, 1100), 0);
+            assert_eq!(token.locked_balance_of(acc.bob), 400);
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.transfer(acc.charlie, 101, vec![]), Err(PSP22Error::TokensLocked));
+            assert_eq!(token.burn(acc.bob, 101), Err(PSP22Error::TokensLocked));
+            assert_eq!(token.transfer(acc.charlie, 100, vec![]), Ok(()));
+
+            // Half of the schedule is vested.
+            set_block_timestamp::<E>(1500);
+            assert_eq!(token.vested_amount(acc.bob, 1500), 200);
+            assert_eq!(token.locked_balance_of(acc.bob), 200);
+            assert_eq!(token.approve(acc.charlie, 300), Ok(()));
+            set_caller::<E>(acc.charlie);
+            assert_eq!(
+                token.transfer_from(acc.bob, acc.charlie, 201, vec![]),
+                Err(PSP22Error::TokensLocked)
+            );
+            assert_eq!(token.transfer_from(acc.bob, acc.charlie, 200, vec![]), Ok(()));
+
+            // Everything is vested.
+            set_block_timestamp::<E>(2000);
+            assert_eq!(token.locked_balance_of(acc.bob), 0);
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.burn(acc.bob, 200), Ok(()));
+            assert_eq!(token.balance_of(acc.bob), 0);
+        }
+
+        #[ink::test]
+        fn cliff_vesting_unlocks_balance_at_once() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new, CAP, CAP);
+            assert_eq!(token.grant_role(VESTING_MANAGER_ROLE, Some(acc.alice)), Ok(()));
+            let schedule = VestingSchedule {
+                amount: CAP,
+                start: 1000,
+                cliff: 1000,
+                duration: 0,
+            };
+            assert_eq!(token.add_vesting_schedule(acc.alice, schedule), Ok(()));
+
+            set_block_timestamp::<E>(999);
+            assert_eq!(token.locked_balance_of(acc.alice), CAP);
+            assert_eq!(token.transfer(acc.bob, 1, vec![]), Err(PSP22Error::TokensLocked));
+
+            set_block_timestamp::<E>(1000);
+            assert_eq!(token.locked_balance_of(acc.alice), 0);
+            assert_eq!(token.transfer(acc.bob, CAP, vec![]), Ok(()));
+        }
+
+        #[ink::test]
+        fn mint_requires_minter_allowance() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new, CAP, 0);
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.mint(acc.bob, 1), Err(PSP22Error::MissingRole));
+            // Only the admin of the minter role can configure minters.
+            assert_eq!(token.configure_minter(acc.bob, 100), Err(PSP22Error::MissingRole));
+
+            set_caller::<E>(acc.alice);
+            let start = recorded_events().count();
+            assert_eq!(token.configure_minter(acc.bob, 100), Ok(()));
+            assert_eq!(token.minter_allowance(acc.bob), 100);
+            assert!(token.has_role(MINTER_ROLE, Some(acc.bob)));
+            let events = decode_events(start);
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                &events[0],
+                Event::MinterConfigured(MinterConfigured { minter, allowance })
+                    if *minter == acc.bob && *allowance == 100
+            ));
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.mint(acc.charlie, 60), Ok(()));
+            assert_eq!(token.minter_allowance(acc.bob), 40);
+            assert_eq!(token.mint(acc.charlie, 41), Err(PSP22Error::MinterAllowanceExceeded));
+            assert_eq!(token.balance_of(acc.charlie), 60);
+        }
+
+        #[ink::test]
+        fn mint_respects_cap_and_removed_minters() {
+            let acc = default_accounts::<E>();
+            let mut token = setup(Token::new, CAP, CAP);
+            assert_eq!(token.configure_minter(acc.bob, CAP), Ok(()));
+
+            // The global cap still applies to every minter.
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.mint(acc.bob, 1), Err(PSP22Error::CapExceeded));
+            set_caller::<E>(acc.alice);
+            assert_eq!(token.burn(acc.alice, 10), Ok(()));
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.mint(acc.bob, 10), Ok(()));
+
+            set_caller::<E>(acc.alice);
+            let start = recorded_events().count();
+            assert_eq!(token.remove_minter(acc.bob), Ok(()));
+            assert_eq!(token.minter_allowance(acc.bob), 0);
+            assert!(!token.has_role(MINTER_ROLE, Some(acc.bob)));
+            let events = decode_events(start);
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                &events[0],
+                Event::MinterRemoved(MinterRemoved { minter }) if *minter == acc.bob
+            ));
+            assert_eq!(token.remove_minter(acc.bob), Err(PSP22Error::MissingRole));
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.mint(acc.bob, 1), Err(PSP22Error::MissingRole));
+        }
     }
 }
\ No newline at end of file
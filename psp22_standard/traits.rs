@@ -3,6 +3,7 @@ use ink::{
     primitives::AccountId,
 };
 use crate::access_control::RoleType;
+use crate::data::VestingSchedule;
 use crate::errors::PSP22Error;
 
 #[ink::trait_definition]
@@ -122,3 +123,46 @@ pub trait AdminTrait {
     #[ink(message)]
     fn get_balance(&mut self) -> Result<u128, PSP22Error>;
 }
+
+
+#[ink::trait_definition]
+pub trait PSP22Soulbound {
+    #[ink(message)]
+    fn is_soulbound(&self) -> bool;
+
+    #[ink(message)]
+    fn is_transferable_account(&self, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn add_transferable_account(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn remove_transferable_account(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Vesting {
+    #[ink(message)]
+    fn schedules_of(&self, account: AccountId) -> Vec<VestingSchedule>;
+
+    #[ink(message)]
+    fn vested_amount(&self, account: AccountId, timestamp: u64) -> u128;
+
+    #[ink(message)]
+    fn locked_balance_of(&self, account: AccountId) -> u128;
+
+    #[ink(message)]
+    fn add_vesting_schedule(&mut self, account: AccountId, schedule: VestingSchedule) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Minters {
+    #[ink(message)]
+    fn minter_allowance(&self, minter: AccountId) -> u128;
+
+    #[ink(message)]
+    fn configure_minter(&mut self, minter: AccountId, allowance: u128) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn remove_minter(&mut self, minter: AccountId) -> Result<(), PSP22Error>;
+}
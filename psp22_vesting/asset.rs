@@ -0,0 +1,67 @@
+use ink::{
+    env::DefaultEnvironment,
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+use psp22::{PSP22Error, PSP22};
+
+/// Returns the balance of `owner` in the PSP22 contract `token`.
+#[cfg(not(test))]
+pub(crate) fn balance_of(token: AccountId, owner: AccountId) -> u128 {
+    let token: ink::contract_ref!(psp22::PSP22, DefaultEnvironment) = token.into();
+    token.balance_of(owner)
+}
+
+/// Transfers `value` of the PSP22 contract `token` held by the caller to `to`.
+#[cfg(not(test))]
+pub(crate) fn transfer(token: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+    let mut token: ink::contract_ref!(psp22::PSP22, DefaultEnvironment) = token.into();
+    token.transfer(to, value, Vec::new())
+}
+
+#[cfg(test)]
+pub(crate) use mock::{balance_of, transfer};
+
+/// Cross-contract calls are not supported off-chain, so tests deploy the
+/// PSP22 token contract of this repository in memory and call it directly.
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+    use ink::env::test::{set_callee, set_caller};
+    use psp22::token::Token;
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    std::thread_local! {
+        static TOKENS: RefCell<BTreeMap<AccountId, Token>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    /// Makes `token` reachable at the address `at`.
+    pub fn deploy(at: AccountId, token: Token) {
+        TOKENS.with(|tokens| tokens.borrow_mut().insert(at, token));
+    }
+
+    /// Calls `f` with the token deployed at `at`, as if the calling contract was its caller.
+    pub fn call<R>(at: AccountId, f: impl FnOnce(&mut Token) -> R) -> R {
+        let caller = ink::env::caller::<DefaultEnvironment>();
+        let callee = ink::env::account_id::<DefaultEnvironment>();
+        set_caller::<DefaultEnvironment>(callee);
+        set_callee::<DefaultEnvironment>(at);
+        let result = TOKENS.with(|tokens| {
+            f(tokens
+                .borrow_mut()
+                .get_mut(&at)
+                .expect("no token deployed at the address"))
+        });
+        set_callee::<DefaultEnvironment>(callee);
+        set_caller::<DefaultEnvironment>(caller);
+        result
+    }
+
+    pub fn balance_of(token: AccountId, owner: AccountId) -> u128 {
+        call(token, |token| token.balance_of(owner))
+    }
+
+    pub fn transfer(token: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+        call(token, |token| token.transfer(to, value, Vec::new()))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::errors::VestingError;
+use ink::{primitives::AccountId, storage::Mapping};
+use psp22::VestingSchedule;
+
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct VestingData {
+    /// Timestamps (in milliseconds).
+    start: u64,
+    cliff: u64,
+    duration: u64,
+    revocable: bool,
+    /// Amount of every token released to the beneficiary so far.
+    released: Mapping<AccountId, u128>,
+    revoked: Mapping<AccountId, ()>,
+}
+
+impl VestingData {
+    pub fn new(start: u64, cliff: u64, duration: u64, revocable: bool) -> VestingData {
+        VestingData {
+            start,
+            cliff,
+            duration,
+            revocable,
+            ..Default::default()
+        }
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn cliff(&self) -> u64 {
+        self.cliff
+    }
+
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
+
+    pub fn is_revocable(&self) -> bool {
+        self.revocable
+    }
+
+    pub fn is_revoked(&self, token: AccountId) -> bool {
+        self.revoked.contains(token)
+    }
+
+    pub fn released(&self, token: AccountId) -> u128 {
+        self.released.get(token).unwrap_or_default()
+    }
+
+    /// Returns the amount of `token` vested at `timestamp`, out of the
+    /// `balance` held by the wallet and everything released before.
+    pub fn vested_amount(&self, token: AccountId, balance: u128, timestamp: u64) -> u128 {
+        let released = self.released(token);
+        let total = balance.saturating_add(released);
+        if self.is_revoked(token) {
+            return total;
+        }
+        VestingSchedule {
+            amount: total,
+            start: self.start,
+            cliff: self.cliff,
+            duration: self.duration,
+        }
+        .vested_amount(timestamp)
+    }
+
+    pub fn releasable(&self, token: AccountId, balance: u128, now: u64) -> u128 {
+        self.vested_amount(token, balance, now)
+            .saturating_sub(self.released(token))
+    }
+
+    /// Records the release of every vested `token` and returns the amount to
+    /// transfer to the beneficiary.
+    pub fn release(&mut self, token: AccountId, balance: u128, now: u64) -> u128 {
+        let amount = self.releasable(token, balance, now);
+        if amount > 0 {
+            self.released
+                .insert(token, &self.released(token).saturating_add(amount));
+        }
+        amount
+    }
+
+    /// Stops the vesting of `token` and returns the unvested amount to refund.
+    /// Tokens vested until `now` stay releasable by the beneficiary.
+    pub fn revoke(&mut self, token: AccountId, balance: u128, now: u64) -> Result<u128, VestingError> {
+        if !self.revocable {
+            return Err(VestingError::NotRevocable);
+        }
+        if self.is_revoked(token) {
+            return Err(VestingError::AlreadyRevoked);
+        }
+        let refund = balance.saturating_sub(self.releasable(token, balance, now));
+        self.revoked.insert(token, &());
+        Ok(refund)
+    }
+}
@@ -0,0 +1,24 @@
+use ink::prelude::string::String;
+use psp22::PSP22Error;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VestingError {
+    /// Custom error type for cases if writer of traits added own restrictions
+    Custom(String),
+    /// Returned if a call to the vested token fails
+    PSP22(PSP22Error),
+    CallerIsNotOwner,
+    /// Returned if the wallet was created without revocability
+    NotRevocable,
+    /// Returned if the grant of the token is already revoked
+    AlreadyRevoked,
+    /// Returned if the cliff is not within the vesting period
+    InvalidSchedule,
+}
+
+impl From<PSP22Error> for VestingError {
+    fn from(error: PSP22Error) -> Self {
+        VestingError::PSP22(error)
+    }
+}
@@ -0,0 +1,269 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+mod asset;
+mod data;
+mod errors;
+mod traits;
+
+pub use data::VestingData;
+pub use errors::VestingError;
+pub use traits::Vesting;
+
+#[cfg(feature = "contract")]
+#[ink::contract]
+mod vesting {
+    use crate::{
+        asset,
+        Vesting,
+        VestingData,
+        VestingError,
+    };
+    use ink::codegen::EmitEvent;
+
+    #[ink(storage)]
+    pub struct VestingWallet {
+        data: VestingData,
+        owner: AccountId,
+        beneficiary: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        token: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct Revoked {
+        #[ink(topic)]
+        token: AccountId,
+        refund: u128,
+    }
+
+    impl VestingWallet {
+        /// Vests every PSP22 token sent to the wallet for `beneficiary`, linearly
+        /// from `start` to `start + duration` (timestamps in milliseconds). Nothing
+        /// is vested before `cliff`, which has to be within that period. With
+        /// `revocable` the `contract_owner` can take the unvested tokens back.
+        #[ink(constructor)]
+        pub fn new(
+            contract_owner: AccountId,
+            beneficiary: AccountId,
+            start: u64,
+            cliff: u64,
+            duration: u64,
+            revocable: bool,
+        ) -> Result<Self, VestingError> {
+            if cliff < start || cliff > start.saturating_add(duration) {
+                return Err(VestingError::InvalidSchedule)
+            }
+            Ok(Self {
+                data: VestingData::new(start, cliff, duration, revocable),
+                owner: contract_owner,
+                beneficiary,
+            })
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn beneficiary(&self) -> AccountId {
+            self.beneficiary
+        }
+
+        #[ink(message)]
+        pub fn start(&self) -> u64 {
+            self.data.start()
+        }
+
+        #[ink(message)]
+        pub fn cliff(&self) -> u64 {
+            self.data.cliff()
+        }
+
+        #[ink(message)]
+        pub fn duration(&self) -> u64 {
+            self.data.duration()
+        }
+
+        #[ink(message)]
+        pub fn is_revocable(&self) -> bool {
+            self.data.is_revocable()
+        }
+
+        fn balance_of(&self, token: AccountId) -> u128 {
+            asset::balance_of(token, self.env().account_id())
+        }
+    }
+
+    impl Vesting for VestingWallet {
+        #[ink(message)]
+        fn release(&mut self, token: AccountId) -> Result<(), VestingError> {
+            let balance = self.balance_of(token);
+            let amount = self.data.release(token, balance, self.env().block_timestamp());
+            if amount > 0 {
+                asset::transfer(token, self.beneficiary, amount)?;
+                EmitEvent::<VestingWallet>::emit_event(self.env(), Released { token, amount });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn revoke(&mut self, token: AccountId) -> Result<(), VestingError> {
+            if self.owner != self.env().caller() {
+                return Err(VestingError::CallerIsNotOwner)
+            }
+            let balance = self.balance_of(token);
+            let refund = self.data.revoke(token, balance, self.env().block_timestamp())?;
+            if refund > 0 {
+                asset::transfer(token, self.owner, refund)?;
+            }
+            EmitEvent::<VestingWallet>::emit_event(self.env(), Revoked { token, refund });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn releasable(&self, token: AccountId) -> u128 {
+            self.data
+                .releasable(token, self.balance_of(token), self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn released(&self, token: AccountId) -> u128 {
+            self.data.released(token)
+        }
+
+        #[ink(message)]
+        fn vested_amount(&self, token: AccountId, timestamp: u64) -> u128 {
+            self.data.vested_amount(token, self.balance_of(token), timestamp)
+        }
+
+        #[ink(message)]
+        fn is_revoked(&self, token: AccountId) -> bool {
+            self.data.is_revoked(token)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::asset::mock;
+        use ink::env::{test::*, DefaultEnvironment as E};
+        use psp22::{token::Token, AccessControl, PSP22Mintable, PSP22Minters};
+
+        const SUPPLY: u128 = 1_000;
+
+        // Wallet deployed at Django and owned by Alice, vesting for Bob from
+        // 1s to 11s with a cliff at 3s. It holds 1000 tokens deployed at Frank.
+        fn setup(revocable: bool) -> VestingWallet {
+            let accounts = default_accounts::<E>();
+            set_callee::<E>(accounts.frank);
+            let mut token = Token::new(SUPPLY, None, None, 0);
+            assert_eq!(token.init_admin_role(), Ok(()));
+            assert_eq!(token.configure_minter(accounts.alice, SUPPLY), Ok(()));
+            assert_eq!(token.mint(accounts.django, SUPPLY), Ok(()));
+            mock::deploy(accounts.frank, token);
+            set_callee::<E>(accounts.django);
+            VestingWallet::new(accounts.alice, accounts.bob, 1_000, 3_000, 10_000, revocable).unwrap()
+        }
+
+        fn balance_of(owner: AccountId) -> u128 {
+            let accounts = default_accounts::<E>();
+            asset::balance_of(accounts.frank, owner)
+        }
+
+        #[ink::test]
+        fn constructor_works() {
+            let accounts = default_accounts::<E>();
+            let wallet = setup(false);
+            assert_eq!(wallet.owner(), accounts.alice);
+            assert_eq!(wallet.beneficiary(), accounts.bob);
+            assert_eq!(wallet.start(), 1_000);
+            assert_eq!(wallet.cliff(), 3_000);
+            assert_eq!(wallet.duration(), 10_000);
+            assert!(!wallet.is_revocable());
+            assert!(!wallet.is_revoked(accounts.frank));
+            assert_eq!(wallet.released(accounts.frank), 0);
+            assert_eq!(wallet.vested_amount(accounts.frank, 11_000), SUPPLY);
+        }
+
+        #[ink::test]
+        fn constructor_validates_cliff() {
+            let accounts = default_accounts::<E>();
+            // The cliff has to be within the vesting period.
+            assert!(matches!(
+                VestingWallet::new(accounts.alice, accounts.bob, 1_000, 999, 10_000, false),
+                Err(VestingError::InvalidSchedule)
+            ));
+            assert!(matches!(
+                VestingWallet::new(accounts.alice, accounts.bob, 1_000, 11_001, 10_000, false),
+                Err(VestingError::InvalidSchedule)
+            ));
+            assert!(VestingWallet::new(accounts.alice, accounts.bob, 1_000, 1_000, 0, false).is_ok());
+        }
+
+        #[ink::test]
+        fn release_follows_schedule() {
+            let accounts = default_accounts::<E>();
+            let mut wallet = setup(false);
+            // Nothing is released before the cliff.
+            set_block_timestamp::<E>(2_999);
+            assert_eq!(wallet.releasable(accounts.frank), 0);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            assert_eq!(balance_of(accounts.bob), 0);
+            // Vesting is linear from the start once the cliff is reached.
+            set_block_timestamp::<E>(3_000);
+            assert_eq!(wallet.releasable(accounts.frank), 200);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            assert_eq!(wallet.releasable(accounts.frank), 0);
+            set_block_timestamp::<E>(6_000);
+            // Anyone can release tokens to the beneficiary.
+            set_caller::<E>(accounts.charlie);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            assert_eq!(wallet.released(accounts.frank), 500);
+            assert_eq!(balance_of(accounts.bob), 500);
+            assert_eq!(wallet.vested_amount(accounts.frank, 6_000), 500);
+            // Everything is released at the end.
+            set_block_timestamp::<E>(20_000);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            assert_eq!(balance_of(accounts.bob), SUPPLY);
+            assert_eq!(balance_of(accounts.django), 0);
+        }
+
+        #[ink::test]
+        fn revoke_refunds_unvested_tokens() {
+            let accounts = default_accounts::<E>();
+            let mut wallet = setup(true);
+            set_block_timestamp::<E>(3_000);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            // Only the owner can revoke.
+            set_block_timestamp::<E>(5_000);
+            set_caller::<E>(accounts.bob);
+            assert_eq!(wallet.revoke(accounts.frank), Err(VestingError::CallerIsNotOwner));
+            // 400 tokens are vested at 5s, 200 of them not released yet.
+            set_caller::<E>(accounts.alice);
+            assert_eq!(wallet.revoke(accounts.frank), Ok(()));
+            assert!(wallet.is_revoked(accounts.frank));
+            assert_eq!(balance_of(accounts.alice), 600);
+            assert_eq!(wallet.revoke(accounts.frank), Err(VestingError::AlreadyRevoked));
+            // Vesting stopped, the beneficiary keeps what was vested.
+            set_block_timestamp::<E>(20_000);
+            assert_eq!(wallet.releasable(accounts.frank), 200);
+            assert_eq!(wallet.release(accounts.frank), Ok(()));
+            assert_eq!(balance_of(accounts.bob), 400);
+            assert_eq!(balance_of(accounts.django), 0);
+        }
+
+        #[ink::test]
+        fn irrevocable_wallet_can_not_be_revoked() {
+            let accounts = default_accounts::<E>();
+            let mut wallet = setup(false);
+            assert_eq!(wallet.revoke(accounts.frank), Err(VestingError::NotRevocable));
+            assert_eq!(balance_of(accounts.django), SUPPLY);
+        }
+    }
+}
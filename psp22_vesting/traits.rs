@@ -0,0 +1,28 @@
+use ink::primitives::AccountId;
+
+use crate::errors::VestingError;
+
+#[ink::trait_definition]
+pub trait Vesting {
+    /// Transfers every vested `token` to the beneficiary.
+    #[ink(message)]
+    fn release(&mut self, token: AccountId) -> Result<(), VestingError>;
+
+    /// Returns the unvested `token` balance to the owner. Tokens vested so far
+    /// stay releasable by the beneficiary.
+    #[ink(message)]
+    fn revoke(&mut self, token: AccountId) -> Result<(), VestingError>;
+
+    #[ink(message)]
+    fn releasable(&self, token: AccountId) -> u128;
+
+    #[ink(message)]
+    fn released(&self, token: AccountId) -> u128;
+
+    /// Returns the amount of `token` vested at `timestamp`, released or not.
+    #[ink(message)]
+    fn vested_amount(&self, token: AccountId, timestamp: u64) -> u128;
+
+    #[ink(message)]
+    fn is_revoked(&self, token: AccountId) -> bool;
+}
@@ -0,0 +1,105 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Contract receiving PSP34 tokens, which accepts or rejects them depending on
+/// a flag set by its deployer. Used to test safe transfers of collections.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod receiver {
+    use ink::prelude::{string::String, vec::Vec};
+    pub use psp34::{Id, PSP34Receiver, PSP34ReceiverError};
+
+    /// Operator, sender, token id and data of a received token.
+    pub type Received = (AccountId, AccountId, Id, Vec<u8>);
+
+    #[ink(storage)]
+    pub struct Receiver {
+        accept: bool,
+        received_count: u32,
+        last_received: Option<Received>,
+    }
+
+    impl Receiver {
+        #[ink(constructor)]
+        pub fn new(accept: bool) -> Self {
+            Self {
+                accept,
+                received_count: 0,
+                last_received: None,
+            }
+        }
+
+        #[ink(message)]
+        pub fn set_accept(&mut self, accept: bool) {
+            self.accept = accept;
+        }
+
+        /// Number of tokens accepted so far.
+        #[ink(message)]
+        pub fn received_count(&self) -> u32 {
+            self.received_count
+        }
+
+        /// Arguments of the last accepted `before_received` call.
+        #[ink(message)]
+        pub fn last_received(&self) -> Option<Received> {
+            self.last_received.clone()
+        }
+    }
+
+    impl PSP34Receiver for Receiver {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            id: Id,
+            data: Vec<u8>,
+        ) -> Result<(), PSP34ReceiverError> {
+            if !self.accept {
+                return Err(PSP34ReceiverError::TransferRejected(String::from(
+                    "Receiver does not accept tokens",
+                )));
+            }
+            self.received_count = self.received_count.saturating_add(1);
+            self.last_received = Some((operator, from, id, data));
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        #[ink::test]
+        fn accepts_tokens() {
+            let accounts = default_accounts::<E>();
+            let mut receiver = Receiver::new(true);
+            assert_eq!(
+                receiver.before_received(accounts.bob, accounts.alice, Id::U64(1), vec![1, 2]),
+                Ok(())
+            );
+            assert_eq!(receiver.received_count(), 1);
+            assert_eq!(
+                receiver.last_received(),
+                Some((accounts.bob, accounts.alice, Id::U64(1), vec![1, 2]))
+            );
+        }
+
+        #[ink::test]
+        fn rejects_tokens() {
+            let accounts = default_accounts::<E>();
+            let mut receiver = Receiver::new(false);
+            assert!(matches!(
+                receiver.before_received(accounts.bob, accounts.alice, Id::U64(1), vec![]),
+                Err(PSP34ReceiverError::TransferRejected(_))
+            ));
+            assert_eq!(receiver.received_count(), 0);
+            receiver.set_accept(true);
+            assert_eq!(
+                receiver.before_received(accounts.bob, accounts.alice, Id::U64(1), vec![]),
+                Ok(())
+            );
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use ink::{
+    env::DefaultEnvironment,
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+use psp22::{PSP22Error, PSP22};
+use psp34::{Id, PSP34Error, PSP34TransferLock, PSP34};
+
+/// Returns the owner of token `id` of the PSP34 contract `collection`.
+#[cfg(not(test))]
+pub(crate) fn owner_of(collection: AccountId, id: Id) -> Option<AccountId> {
+    let collection: ink::contract_ref!(PSP34, DefaultEnvironment) = collection.into();
+    collection.owner_of(id)
+}
+
+/// Transfers token `id` of the PSP34 contract `collection` to `to`, with the
+/// caller as operator.
+#[cfg(not(test))]
+pub(crate) fn transfer_token(collection: AccountId, to: AccountId, id: Id) -> Result<(), PSP34Error> {
+    let mut collection: ink::contract_ref!(PSP34, DefaultEnvironment) = collection.into();
+    collection.transfer(to, id, Vec::new())
+}
+
+#[cfg(not(test))]
+pub(crate) fn lock_transfer(collection: AccountId, id: Id) -> Result<(), PSP34Error> {
+    let mut collection: ink::contract_ref!(PSP34TransferLock, DefaultEnvironment) = collection.into();
+    collection.lock_transfer(id)
+}
+
+#[cfg(not(test))]
+pub(crate) fn unlock_transfer(collection: AccountId, id: Id) -> Result<(), PSP34Error> {
+    let mut collection: ink::contract_ref!(PSP34TransferLock, DefaultEnvironment) = collection.into();
+    collection.unlock_transfer(id)
+}
+
+/// Returns the balance of `owner` in the PSP22 contract `token`.
+#[cfg(not(test))]
+pub(crate) fn balance_of(token: AccountId, owner: AccountId) -> u128 {
+    let token: ink::contract_ref!(PSP22, DefaultEnvironment) = token.into();
+    token.balance_of(owner)
+}
+
+/// Transfers `value` of the PSP22 contract `token` held by the caller to `to`.
+#[cfg(not(test))]
+pub(crate) fn transfer(token: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+    let mut token: ink::contract_ref!(PSP22, DefaultEnvironment) = token.into();
+    token.transfer(to, value, Vec::new())
+}
+
+#[cfg(test)]
+pub(crate) use mock::{balance_of, lock_transfer, owner_of, transfer, transfer_token, unlock_transfer};
+
+/// Cross-contract calls are not supported off-chain, so tests deploy the
+/// PSP34 and PSP22 token contracts of this repository in memory and call them
+/// directly.
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+    use ink::env::test::{set_callee, set_caller};
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    type Collection = psp34::token::Token;
+    type Token = psp22::token::Token;
+
+    std::thread_local! {
+        static COLLECTIONS: RefCell<BTreeMap<AccountId, Collection>> = const { RefCell::new(BTreeMap::new()) };
+        static TOKENS: RefCell<BTreeMap<AccountId, Token>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    /// Makes `collection` reachable at the address `at`.
+    pub fn deploy_collection(at: AccountId, collection: Collection) {
+        COLLECTIONS.with(|collections| collections.borrow_mut().insert(at, collection));
+    }
+
+    /// Makes `token` reachable at the address `at`.
+    pub fn deploy_token(at: AccountId, token: Token) {
+        TOKENS.with(|tokens| tokens.borrow_mut().insert(at, token));
+    }
+
+    /// Runs `f` as a call of the current contract to the contract at `at`.
+    fn as_call<R>(at: AccountId, f: impl FnOnce() -> R) -> R {
+        let caller = ink::env::caller::<DefaultEnvironment>();
+        let callee = ink::env::account_id::<DefaultEnvironment>();
+        set_caller::<DefaultEnvironment>(callee);
+        set_callee::<DefaultEnvironment>(at);
+        let result = f();
+        set_callee::<DefaultEnvironment>(callee);
+        set_caller::<DefaultEnvironment>(caller);
+        result
+    }
+
+    /// Calls `f` with the collection deployed at `at`.
+    pub fn call_collection<R>(at: AccountId, f: impl FnOnce(&mut Collection) -> R) -> R {
+        as_call(at, || {
+            COLLECTIONS.with(|collections| {
+                f(collections
+                    .borrow_mut()
+                    .get_mut(&at)
+                    .expect("no collection deployed at the address"))
+            })
+        })
+    }
+
+    /// Calls `f` with the token deployed at `at`.
+    pub fn call_token<R>(at: AccountId, f: impl FnOnce(&mut Token) -> R) -> R {
+        as_call(at, || {
+            TOKENS.with(|tokens| {
+                f(tokens
+                    .borrow_mut()
+                    .get_mut(&at)
+                    .expect("no token deployed at the address"))
+            })
+        })
+    }
+
+    pub fn owner_of(collection: AccountId, id: Id) -> Option<AccountId> {
+        call_collection(collection, |collection| collection.owner_of(id))
+    }
+
+    pub fn transfer_token(collection: AccountId, to: AccountId, id: Id) -> Result<(), PSP34Error> {
+        call_collection(collection, |collection| collection.transfer(to, id, Vec::new()))
+    }
+
+    pub fn lock_transfer(collection: AccountId, id: Id) -> Result<(), PSP34Error> {
+        call_collection(collection, |collection| collection.lock_transfer(id))
+    }
+
+    pub fn unlock_transfer(collection: AccountId, id: Id) -> Result<(), PSP34Error> {
+        call_collection(collection, |collection| collection.unlock_transfer(id))
+    }
+
+    pub fn balance_of(token: AccountId, owner: AccountId) -> u128 {
+        call_token(token, |token| token.balance_of(owner))
+    }
+
+    pub fn transfer(token: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+        call_token(token, |token| token.transfer(to, value, Vec::new()))
+    }
+}
@@ -0,0 +1,155 @@
+use crate::errors::StakingError;
+use ink::{primitives::AccountId, storage::Mapping};
+use psp34::Id;
+
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+/// Timestamps are in milliseconds, reward rates per second.
+const MILLIS_PER_SECOND: u128 = 1_000;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct StakerInfo {
+    /// Number of tokens staked by the account.
+    pub staked_count: u32,
+    /// Value of the reward accumulator when the account was last settled.
+    pub reward_snapshot: u128,
+    /// Rewards settled but not claimed yet, scaled by `MILLIS_PER_SECOND`.
+    pub unclaimed: u128,
+}
+
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct StakingData {
+    reward_per_second: u128,
+    /// Reward accrued by a single token staked since the beginning, scaled by
+    /// `MILLIS_PER_SECOND`.
+    reward_per_token: u128,
+    last_update: u64,
+    stakes: Mapping<Id, (AccountId, u64)>,
+    stakers: Mapping<AccountId, StakerInfo>,
+    total_staked: u64,
+    /// Rewards accrued by every staker and not claimed yet, scaled by
+    /// `MILLIS_PER_SECOND`.
+    owed: u128,
+}
+
+impl StakingData {
+    pub fn new(reward_per_second: u128) -> StakingData {
+        StakingData {
+            reward_per_second,
+            ..Default::default()
+        }
+    }
+
+    pub fn reward_per_second(&self) -> u128 {
+        self.reward_per_second
+    }
+
+    pub fn total_staked(&self) -> u64 {
+        self.total_staked
+    }
+
+    pub fn stake_info(&self, id: &Id) -> Option<(AccountId, u64)> {
+        self.stakes.get(id)
+    }
+
+    pub fn staked_count(&self, account: AccountId) -> u32 {
+        self.stakers.get(account).unwrap_or_default().staked_count
+    }
+
+    /// Changes the reward rate. Rewards accrued so far keep the old rate.
+    pub fn set_reward_per_second(&mut self, reward_per_second: u128, now: u64) {
+        self.update(now);
+        self.reward_per_second = reward_per_second;
+    }
+
+    /// Returns the rewards stakers accrued until `now` and did not claim yet,
+    /// rounded up.
+    pub fn owed_rewards(&self, now: u64) -> u128 {
+        self.owed_at(now).div_ceil(MILLIS_PER_SECOND)
+    }
+
+    pub fn pending_rewards(&self, account: AccountId, now: u64) -> u128 {
+        let staker = self.stakers.get(account).unwrap_or_default();
+        let accrued = (staker.staked_count as u128)
+            .saturating_mul(self.reward_per_token_at(now).saturating_sub(staker.reward_snapshot));
+        staker.unclaimed.saturating_add(accrued) / MILLIS_PER_SECOND
+    }
+
+    /// Records token `id` as staked by `staker` at `now`.
+    pub fn add_stake(&mut self, staker: AccountId, id: &Id, now: u64) -> Result<(), StakingError> {
+        if self.stakes.contains(id) {
+            return Err(StakingError::AlreadyStaked);
+        }
+        let mut info = self.settle(staker, now);
+        info.staked_count = info.staked_count.checked_add(1).ok_or(StakingError::InvalidInput)?;
+        self.stakers.insert(staker, &info);
+        self.stakes.insert(id, &(staker, now));
+        self.total_staked = self.total_staked.saturating_add(1);
+        Ok(())
+    }
+
+    /// Removes token `id` staked by `staker`. Its rewards stay claimable.
+    pub fn remove_stake(&mut self, staker: AccountId, id: &Id, now: u64) -> Result<(), StakingError> {
+        match self.stakes.get(id) {
+            Some((account, _)) if account == staker => {}
+            _ => return Err(StakingError::NotStaked),
+        }
+        let mut info = self.settle(staker, now);
+        info.staked_count = info.staked_count.saturating_sub(1);
+        self.stakers.insert(staker, &info);
+        self.stakes.remove(id);
+        self.total_staked = self.total_staked.saturating_sub(1);
+        Ok(())
+    }
+
+    /// Settles and returns every reward of `staker`, leaving sub-unit
+    /// remainders for the next claim.
+    pub fn claim(&mut self, staker: AccountId, now: u64) -> u128 {
+        let mut info = self.settle(staker, now);
+        let amount = info.unclaimed / MILLIS_PER_SECOND;
+        info.unclaimed %= MILLIS_PER_SECOND;
+        self.owed = self.owed.saturating_sub(amount.saturating_mul(MILLIS_PER_SECOND));
+        if info.staked_count == 0 && info.unclaimed == 0 {
+            self.stakers.remove(staker);
+        } else {
+            self.stakers.insert(staker, &info);
+        }
+        amount
+    }
+
+    fn reward_per_token_at(&self, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_update) as u128;
+        self.reward_per_token
+            .saturating_add(self.reward_per_second.saturating_mul(elapsed))
+    }
+
+    fn owed_at(&self, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_update) as u128;
+        self.owed.saturating_add(
+            (self.total_staked as u128)
+                .saturating_mul(self.reward_per_second)
+                .saturating_mul(elapsed),
+        )
+    }
+
+    fn update(&mut self, now: u64) {
+        self.owed = self.owed_at(now);
+        self.reward_per_token = self.reward_per_token_at(now);
+        self.last_update = now;
+    }
+
+    /// Moves the rewards accrued by `staker` until `now` to its unclaimed
+    /// balance and returns its up to date info.
+    fn settle(&mut self, staker: AccountId, now: u64) -> StakerInfo {
+        self.update(now);
+        let mut info = self.stakers.get(staker).unwrap_or_default();
+        let accrued = (info.staked_count as u128)
+            .saturating_mul(self.reward_per_token.saturating_sub(info.reward_snapshot));
+        info.unclaimed = info.unclaimed.saturating_add(accrued);
+        info.reward_snapshot = self.reward_per_token;
+        info
+    }
+}
@@ -0,0 +1,36 @@
+use ink::prelude::string::String;
+use psp22::PSP22Error;
+use psp34::PSP34Error;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum StakingError {
+    /// Custom error type for cases if writer of traits added own restrictions
+    Custom(String),
+    /// Returned if a call to the staked collection fails
+    PSP34(PSP34Error),
+    /// Returned if a call to the reward token fails
+    PSP22(PSP22Error),
+    CallerIsNotOwner,
+    /// Returned if the caller does not own the token it stakes
+    NotTokenOwner,
+    /// Returned if the token is already staked
+    AlreadyStaked,
+    /// Returned if the token is not staked by the caller
+    NotStaked,
+    /// Returned if a withdrawal would leave less reward tokens than owed to stakers
+    InsufficientRewards,
+    InvalidInput,
+}
+
+impl From<PSP34Error> for StakingError {
+    fn from(error: PSP34Error) -> Self {
+        StakingError::PSP34(error)
+    }
+}
+
+impl From<PSP22Error> for StakingError {
+    fn from(error: PSP22Error) -> Self {
+        StakingError::PSP22(error)
+    }
+}
@@ -0,0 +1,390 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+mod assets;
+mod data;
+mod errors;
+mod traits;
+
+pub use data::{StakerInfo, StakingData};
+pub use errors::StakingError;
+pub use traits::Staking;
+
+#[cfg(feature = "contract")]
+#[ink::contract]
+mod staking {
+    use crate::{
+        assets,
+        Staking,
+        StakingData,
+        StakingError,
+    };
+    use ink::prelude::vec::Vec;
+    use psp34::Id;
+
+    #[ink(storage)]
+    pub struct StakingContract {
+        data: StakingData,
+        owner: AccountId,
+        collection: AccountId,
+        reward_token: AccountId,
+        lock_in_place: bool,
+    }
+
+    impl StakingContract {
+        /// Stakes tokens of `collection` for `reward_per_second` of `reward_token`
+        /// per staked token. With `lock_in_place` staked tokens stay in the wallet
+        /// of their owner, transfer locked by the collection, instead of being
+        /// escrowed by the staking contract.
+        #[ink(constructor)]
+        pub fn new(
+            contract_owner: AccountId,
+            collection: AccountId,
+            reward_token: AccountId,
+            reward_per_second: u128,
+            lock_in_place: bool,
+        ) -> Self {
+            Self {
+                data: StakingData::new(reward_per_second),
+                owner: contract_owner,
+                collection,
+                reward_token,
+                lock_in_place,
+            }
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn collection(&self) -> AccountId {
+            self.collection
+        }
+
+        #[ink(message)]
+        pub fn reward_token(&self) -> AccountId {
+            self.reward_token
+        }
+
+        #[ink(message)]
+        pub fn is_lock_in_place(&self) -> bool {
+            self.lock_in_place
+        }
+    }
+
+    impl Staking for StakingContract {
+        #[ink(message)]
+        fn stake(&mut self, ids: Vec<Id>) -> Result<(), StakingError> {
+            if ids.is_empty() {
+                return Err(StakingError::InvalidInput)
+            }
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            for id in ids {
+                if assets::owner_of(self.collection, id.clone()) != Some(caller) {
+                    return Err(StakingError::NotTokenOwner)
+                }
+                self.data.add_stake(caller, &id, now)?;
+                if self.lock_in_place {
+                    assets::lock_transfer(self.collection, id)?;
+                } else {
+                    assets::transfer_token(self.collection, self.env().account_id(), id)?;
+                }
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn unstake(&mut self, ids: Vec<Id>) -> Result<(), StakingError> {
+            if ids.is_empty() {
+                return Err(StakingError::InvalidInput)
+            }
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            for id in ids {
+                self.data.remove_stake(caller, &id, now)?;
+                if self.lock_in_place {
+                    assets::unlock_transfer(self.collection, id)?;
+                } else {
+                    assets::transfer_token(self.collection, caller, id)?;
+                }
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn claim_rewards(&mut self) -> Result<(), StakingError> {
+            let caller = self.env().caller();
+            let amount = self.data.claim(caller, self.env().block_timestamp());
+            if amount > 0 {
+                assets::transfer(self.reward_token, caller, amount)?;
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn pending_rewards(&self, account: AccountId) -> u128 {
+            self.data.pending_rewards(account, self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn stake_info(&self, id: Id) -> Option<(AccountId, u64)> {
+            self.data.stake_info(&id)
+        }
+
+        #[ink(message)]
+        fn staked_count(&self, account: AccountId) -> u32 {
+            self.data.staked_count(account)
+        }
+
+        #[ink(message)]
+        fn total_staked(&self) -> u64 {
+            self.data.total_staked()
+        }
+
+        #[ink(message)]
+        fn reward_per_second(&self) -> u128 {
+            self.data.reward_per_second()
+        }
+
+        #[ink(message)]
+        fn set_reward_per_second(&mut self, reward_per_second: u128) -> Result<(), StakingError> {
+            if self.owner != self.env().caller() {
+                return Err(StakingError::CallerIsNotOwner)
+            }
+            self.data.set_reward_per_second(reward_per_second, self.env().block_timestamp());
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn owed_rewards(&self) -> u128 {
+            self.data.owed_rewards(self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn withdraw_rewards(&mut self, amount: u128) -> Result<(), StakingError> {
+            let caller = self.env().caller();
+            if self.owner != caller {
+                return Err(StakingError::CallerIsNotOwner)
+            }
+            let balance = assets::balance_of(self.reward_token, self.env().account_id());
+            let owed = self.data.owed_rewards(self.env().block_timestamp());
+            if amount > balance.saturating_sub(owed) {
+                return Err(StakingError::InsufficientRewards)
+            }
+            assets::transfer(self.reward_token, caller, amount)?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::assets::mock;
+        use ink::env::{test::*, DefaultEnvironment as E};
+        use psp22::{AccessControl, PSP22Mintable, PSP22Minters};
+        use psp34::{PSP34TransferLock, PSP34};
+
+        const REWARDS: u128 = 1_000;
+
+        // Staking contract deployed at Django and owned by Alice, paying 10
+        // reward tokens per second and staked token. Bob holds tokens 1 and 2
+        // of the collection deployed at Eve, Charlie holds token 3, and the
+        // reward token deployed at Frank holds 1000 tokens of the contract.
+        fn setup(lock_in_place: bool) -> StakingContract {
+            let accounts = default_accounts::<E>();
+            set_caller::<E>(accounts.alice);
+            set_callee::<E>(accounts.eve);
+            let mut collection = psp34::token::Token::new(accounts.alice, 10, None, None);
+            for holder in [accounts.bob, accounts.bob, accounts.charlie] {
+                assert_eq!(collection.mint_to(holder), Ok(()));
+            }
+            for holder in [accounts.bob, accounts.charlie] {
+                set_caller::<E>(holder);
+                assert_eq!(collection.approve(accounts.django, None, true), Ok(()));
+            }
+            mock::deploy_collection(accounts.eve, collection);
+            set_caller::<E>(accounts.alice);
+            set_callee::<E>(accounts.frank);
+            let mut token = psp22::token::Token::new(REWARDS, None, None, 0);
+            assert_eq!(token.init_admin_role(), Ok(()));
+            assert_eq!(token.configure_minter(accounts.alice, REWARDS), Ok(()));
+            assert_eq!(token.mint(accounts.django, REWARDS), Ok(()));
+            mock::deploy_token(accounts.frank, token);
+            set_callee::<E>(accounts.django);
+            StakingContract::new(accounts.alice, accounts.eve, accounts.frank, 10, lock_in_place)
+        }
+
+        fn owner_of(id: u64) -> Option<AccountId> {
+            assets::owner_of(default_accounts::<E>().eve, Id::U64(id))
+        }
+
+        fn rewards_of(owner: AccountId) -> u128 {
+            assets::balance_of(default_accounts::<E>().frank, owner)
+        }
+
+        #[ink::test]
+        fn constructor_works() {
+            let accounts = default_accounts::<E>();
+            let staking = setup(false);
+            assert_eq!(staking.owner(), accounts.alice);
+            assert_eq!(staking.collection(), accounts.eve);
+            assert_eq!(staking.reward_token(), accounts.frank);
+            assert_eq!(staking.reward_per_second(), 10);
+            assert!(!staking.is_lock_in_place());
+            assert_eq!(staking.total_staked(), 0);
+            assert_eq!(staking.pending_rewards(accounts.alice), 0);
+            assert_eq!(staking.owed_rewards(), 0);
+        }
+
+        #[ink::test]
+        fn rewards_accrue_per_staked_token() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            // Bob stakes 2 tokens at 1s, Charlie 1 token at 2s.
+            set_block_timestamp::<E>(1_000);
+            assert_eq!(staking.data.add_stake(accounts.bob, &Id::U64(1), 1_000), Ok(()));
+            assert_eq!(staking.data.add_stake(accounts.bob, &Id::U64(2), 1_000), Ok(()));
+            set_block_timestamp::<E>(2_000);
+            assert_eq!(staking.data.add_stake(accounts.charlie, &Id::U64(3), 2_000), Ok(()));
+            assert_eq!(staking.stake_info(Id::U64(3)), Some((accounts.charlie, 2_000)));
+            set_block_timestamp::<E>(4_500);
+            assert_eq!(staking.pending_rewards(accounts.bob), 2 * 35);
+            assert_eq!(staking.pending_rewards(accounts.charlie), 25);
+            assert_eq!(staking.owed_rewards(), 2 * 35 + 25);
+            assert_eq!(staking.total_staked(), 3);
+            // A token can be staked once.
+            assert_eq!(
+                staking.data.add_stake(accounts.charlie, &Id::U64(1), 4_500),
+                Err(StakingError::AlreadyStaked)
+            );
+        }
+
+        #[ink::test]
+        fn unstaked_rewards_stay_claimable() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            assert_eq!(staking.data.add_stake(accounts.bob, &Id::U64(1), 0), Ok(()));
+            // Only the staker can unstake.
+            assert_eq!(
+                staking.data.remove_stake(accounts.charlie, &Id::U64(1), 3_000),
+                Err(StakingError::NotStaked)
+            );
+            assert_eq!(staking.data.remove_stake(accounts.bob, &Id::U64(1), 3_000), Ok(()));
+            set_block_timestamp::<E>(10_000);
+            assert_eq!(staking.pending_rewards(accounts.bob), 30);
+            assert_eq!(staking.staked_count(accounts.bob), 0);
+            assert_eq!(staking.data.claim(accounts.bob, 10_000), 30);
+            assert_eq!(staking.pending_rewards(accounts.bob), 0);
+            assert_eq!(staking.owed_rewards(), 0);
+        }
+
+        #[ink::test]
+        fn set_reward_per_second_works() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            assert_eq!(staking.data.add_stake(accounts.bob, &Id::U64(1), 0), Ok(()));
+            // Only the owner can change the rate.
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.set_reward_per_second(20), Err(StakingError::CallerIsNotOwner));
+            // The new rate applies from now on.
+            set_caller::<E>(accounts.alice);
+            set_block_timestamp::<E>(2_000);
+            assert_eq!(staking.set_reward_per_second(20), Ok(()));
+            set_block_timestamp::<E>(3_000);
+            assert_eq!(staking.pending_rewards(accounts.bob), 2 * 10 + 20);
+        }
+
+        #[ink::test]
+        fn stake_and_unstake_require_tokens() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            assert_eq!(staking.stake(Vec::new()), Err(StakingError::InvalidInput));
+            assert_eq!(staking.unstake(Vec::new()), Err(StakingError::InvalidInput));
+            // Claiming without rewards does not call the reward token.
+            assert_eq!(staking.claim_rewards(), Ok(()));
+            // Only the holder of a token can stake it.
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.stake(vec![Id::U64(3)]), Err(StakingError::NotTokenOwner));
+            assert_eq!(staking.unstake(vec![Id::U64(1)]), Err(StakingError::NotStaked));
+        }
+
+        #[ink::test]
+        fn escrowed_staking_works() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            // Staked tokens are held by the staking contract.
+            set_block_timestamp::<E>(1_000);
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.stake(vec![Id::U64(1), Id::U64(2)]), Ok(()));
+            assert_eq!(owner_of(1), Some(accounts.django));
+            assert_eq!(owner_of(2), Some(accounts.django));
+            set_caller::<E>(accounts.charlie);
+            assert_eq!(staking.unstake(vec![Id::U64(1)]), Err(StakingError::NotStaked));
+            // Claiming pays the rewards accrued so far in the reward token.
+            set_block_timestamp::<E>(3_000);
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.claim_rewards(), Ok(()));
+            assert_eq!(rewards_of(accounts.bob), 2 * 20);
+            // Unstaking returns the tokens, their rewards stay claimable.
+            set_block_timestamp::<E>(4_000);
+            assert_eq!(staking.unstake(vec![Id::U64(1), Id::U64(2)]), Ok(()));
+            assert_eq!(owner_of(1), Some(accounts.bob));
+            assert_eq!(owner_of(2), Some(accounts.bob));
+            set_block_timestamp::<E>(10_000);
+            assert_eq!(staking.claim_rewards(), Ok(()));
+            assert_eq!(rewards_of(accounts.bob), 2 * 30);
+            assert_eq!(rewards_of(accounts.django), REWARDS - 2 * 30);
+        }
+
+        #[ink::test]
+        fn staking_in_place_works() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(true);
+            // Staked tokens stay with their holder, locked by the staking contract.
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.stake(vec![Id::U64(1)]), Ok(()));
+            assert_eq!(owner_of(1), Some(accounts.bob));
+            assert_eq!(
+                mock::call_collection(accounts.eve, |collection| collection.transfer_locker(Id::U64(1))),
+                Some(accounts.django)
+            );
+            // A token locked in place can not be staked twice.
+            assert_eq!(staking.stake(vec![Id::U64(1)]), Err(StakingError::AlreadyStaked));
+            set_block_timestamp::<E>(5_000);
+            assert_eq!(staking.unstake(vec![Id::U64(1)]), Ok(()));
+            assert_eq!(
+                mock::call_collection(accounts.eve, |collection| collection.transfer_locker(Id::U64(1))),
+                None
+            );
+            assert_eq!(staking.claim_rewards(), Ok(()));
+            assert_eq!(rewards_of(accounts.bob), 50);
+            assert_eq!(owner_of(1), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn withdraw_rewards_works() {
+            let accounts = default_accounts::<E>();
+            let mut staking = setup(false);
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.stake(vec![Id::U64(1)]), Ok(()));
+            // Only the owner can withdraw.
+            set_block_timestamp::<E>(30_000);
+            assert_eq!(staking.withdraw_rewards(1), Err(StakingError::CallerIsNotOwner));
+            // Rewards owed to stakers stay in the contract.
+            set_caller::<E>(accounts.alice);
+            assert_eq!(staking.owed_rewards(), 300);
+            assert_eq!(
+                staking.withdraw_rewards(REWARDS - 299),
+                Err(StakingError::InsufficientRewards)
+            );
+            assert_eq!(staking.withdraw_rewards(REWARDS - 300), Ok(()));
+            assert_eq!(rewards_of(accounts.alice), REWARDS - 300);
+            set_caller::<E>(accounts.bob);
+            assert_eq!(staking.claim_rewards(), Ok(()));
+            assert_eq!(rewards_of(accounts.bob), 300);
+            assert_eq!(staking.owed_rewards(), 0);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use ink::{
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+use psp34::Id;
+
+use crate::errors::StakingError;
+
+#[ink::trait_definition]
+pub trait Staking {
+    /// Stakes the given tokens of the caller. The staking contract has to be
+    /// approved for them in the collection beforehand, and for all tokens of
+    /// the caller when they are locked in place.
+    #[ink(message)]
+    fn stake(&mut self, ids: Vec<Id>) -> Result<(), StakingError>;
+
+    /// Returns the given tokens to the caller. Their rewards stay claimable.
+    #[ink(message)]
+    fn unstake(&mut self, ids: Vec<Id>) -> Result<(), StakingError>;
+
+    /// Transfers every reward accrued by the caller.
+    #[ink(message)]
+    fn claim_rewards(&mut self) -> Result<(), StakingError>;
+
+    #[ink(message)]
+    fn pending_rewards(&self, account: AccountId) -> u128;
+
+    /// Returns the staker of token `id` and the timestamp it was staked at.
+    #[ink(message)]
+    fn stake_info(&self, id: Id) -> Option<(AccountId, u64)>;
+
+    #[ink(message)]
+    fn staked_count(&self, account: AccountId) -> u32;
+
+    #[ink(message)]
+    fn total_staked(&self) -> u64;
+
+    #[ink(message)]
+    fn reward_per_second(&self) -> u128;
+
+    /// Changes the reward every staked token accrues per second.
+    #[ink(message)]
+    fn set_reward_per_second(&mut self, reward_per_second: u128) -> Result<(), StakingError>;
+
+    /// Returns the rewards accrued by every staker and not claimed yet.
+    #[ink(message)]
+    fn owed_rewards(&self) -> u128;
+
+    /// Transfers `amount` of the reward token to the owner. Rewards owed to
+    /// stakers can not be withdrawn.
+    #[ink(message)]
+    fn withdraw_rewards(&mut self, amount: u128) -> Result<(), StakingError>;
+}
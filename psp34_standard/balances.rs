@@ -1,14 +1,56 @@
+use crate::data::Id;
+use ink::{prelude::vec::Vec, storage::Mapping};
+
+/// Tokens in the order they were first minted. Burned tokens keep their
+/// position, so the log is only written to by mints.
+#[ink::storage_item]
+#[derive(Default, Debug)]
+pub struct MintLog {
+    tokens: Mapping<u128, Id>,
+    recorded: Mapping<Id, ()>,
+    count: u128,
+}
+
+impl MintLog {
+    /// Appends `id`, unless it was minted before.
+    pub fn record(&mut self, id: &Id) {
+        if !self.recorded.contains(id) {
+            self.tokens.insert(self.count, id);
+            self.recorded.insert(id, &());
+            self.count += 1;
+        }
+    }
+
+    /// Returns the tokens minted at positions `offset` to `offset + limit`.
+    pub fn page(&self, offset: u128, limit: u32) -> Vec<Id> {
+        let end = offset.saturating_add(limit as u128).min(self.count);
+        (offset..end)
+            .filter_map(|index| self.tokens.get(index))
+            .collect()
+    }
+
+    pub fn count(&self) -> u128 {
+        self.count
+    }
+}
+
 #[cfg(not(feature = "enumerable"))]
 pub mod balance_manager {
+    use super::MintLog;
     use crate::{data::Id, PSP34Error};
     use ink::{primitives::AccountId, storage::Mapping};
-    use ink::prelude::string::String;
+    use ink::prelude::{string::String, vec::Vec};
 
+    /// Token counters and the list of tokens of every owner. An owner list is
+    /// kept dense by moving its last token into the slot of a removed one.
     #[ink::storage_item]
     #[derive(Default, Debug)]
     pub struct Balances {
+        owned_tokens: Mapping<(AccountId, u32), Id>,
+        owned_token_indexes: Mapping<(AccountId, Id), u32>,
         owned_tokens_count: Mapping<AccountId, u32>,
         total_supply: u128,
+        minted: MintLog,
     }
 
     impl Balances {
@@ -16,6 +58,24 @@ pub mod balance_manager {
             Default::default()
         }
 
+        /// Returns at most `limit` tokens of `owner`, starting at `offset`.
+        pub fn tokens_of_owner(&self, owner: AccountId, offset: u128, limit: u32) -> Vec<Id> {
+            let end = offset
+                .saturating_add(limit as u128)
+                .min(self.balance_of(&owner) as u128);
+            (offset..end)
+                .filter_map(|index| self.owned_tokens.get((owner, index as u32)))
+                .collect()
+        }
+
+        pub fn minted_tokens(&self, offset: u128, limit: u32) -> Vec<Id> {
+            self.minted.page(offset, limit)
+        }
+
+        pub fn minted_count(&self) -> u128 {
+            self.minted.count()
+        }
+
         pub fn balance_of(&self, owner: &AccountId) -> u32 {
             self.owned_tokens_count.get(owner).unwrap_or(0)
         }
@@ -23,15 +83,17 @@ pub mod balance_manager {
         pub fn increase_balance(
             &mut self,
             owner: &AccountId,
-            _id: &Id,
+            id: &Id,
             increase_supply: bool,
         ) -> Result<(), PSP34Error> {
-            let mut to_balance = self.balance_of(owner);
-            to_balance = to_balance
+            let index = self.balance_of(owner);
+            let to_balance = index
                 .checked_add(1)
                 .ok_or(PSP34Error::Custom(String::from(
                     "Max PSP34 balance exceeded. Max balance limited to 2^32-1.",
                 )))?;
+            self.owned_tokens.insert((owner, index), id);
+            self.owned_token_indexes.insert((owner, id), &index);
             self.owned_tokens_count.insert(owner, &to_balance);
 
             if increase_supply {
@@ -41,17 +103,27 @@ pub mod balance_manager {
                         .ok_or(PSP34Error::Custom(String::from(
                             "Max PSP34 supply exceeded. Max supply limited to 2^128-1.",
                         )))?;
+                self.minted.record(id);
             }
 
             Ok(())
         }
 
-        pub fn decrease_balance(&mut self, owner: &AccountId, _id: &Id, decrease_supply: bool) {
-            let from_balance = self.balance_of(owner);
-            if from_balance <= 1 {
-                self.owned_tokens_count.remove(owner);
-            } else {
-                self.owned_tokens_count.insert(owner, &(from_balance - 1));
+        pub fn decrease_balance(&mut self, owner: &AccountId, id: &Id, decrease_supply: bool) {
+            if let Some(index) = self.owned_token_indexes.take((owner, id)) {
+                let last_index = self.balance_of(owner) - 1;
+                if index != last_index {
+                    if let Some(last_id) = self.owned_tokens.get((owner, last_index)) {
+                        self.owned_tokens.insert((owner, index), &last_id);
+                        self.owned_token_indexes.insert((owner, &last_id), &index);
+                    }
+                }
+                self.owned_tokens.remove((owner, last_index));
+                if last_index == 0 {
+                    self.owned_tokens_count.remove(owner);
+                } else {
+                    self.owned_tokens_count.insert(owner, &last_index);
+                }
             }
             if decrease_supply {
                 self.total_supply -= 1;
@@ -66,13 +138,28 @@ pub mod balance_manager {
 
 #[cfg(feature = "enumerable")]
 pub mod balance_manager {
+    use super::MintLog;
     use crate::{data::Id, PSP34Error};
-    use ink::{prelude::vec::Vec, primitives::AccountId, storage::Mapping};
+    use ink::{
+        prelude::{string::String, vec::Vec},
+        primitives::AccountId,
+        storage::Mapping,
+    };
+
+    /// Key of a token list: `None` for every token of the collection,
+    /// `Some(owner)` for the tokens of a single owner.
+    type ListKey = Option<AccountId>;
 
+    /// Enumerable lists of tokens. Each list is kept dense by moving its last
+    /// token into the slot of a removed one, so adding and removing a token
+    /// touches a constant number of cells.
     #[ink::storage_item]
     #[derive(Default, Debug)]
     pub struct Balances {
-        enumerable: Mapping<Option<AccountId>, Vec<Id>>,
+        enumerable: Mapping<(ListKey, u128), Id>,
+        indexes: Mapping<(ListKey, Id), u128>,
+        counts: Mapping<ListKey, u128>,
+        minted: MintLog,
     }
 
     impl Balances {
@@ -94,33 +181,66 @@ pub mod balance_manager {
                 .ok_or(PSP34Error::TokenNotExists)
         }
 
-        fn _get_value(&self, key: &Option<AccountId>, index: u128) -> Option<Id> {
-            self.enumerable
-                .get(key)
-                .and_then(|values| values.get(usize::try_from(index).unwrap()).cloned())
+        /// Returns at most `limit` tokens of `owner`, starting at `offset`.
+        pub fn tokens_of_owner(&self, owner: AccountId, offset: u128, limit: u32) -> Vec<Id> {
+            self._get_page(&Some(owner), offset, limit)
+        }
+
+        pub fn minted_tokens(&self, offset: u128, limit: u32) -> Vec<Id> {
+            self.minted.page(offset, limit)
+        }
+
+        pub fn minted_count(&self) -> u128 {
+            self.minted.count()
+        }
+
+        fn _get_page(&self, key: &ListKey, offset: u128, limit: u32) -> Vec<Id> {
+            let end = offset
+                .saturating_add(limit as u128)
+                .min(self._count(key));
+            (offset..end)
+                .filter_map(|index| self._get_value(key, index))
+                .collect()
+        }
+
+        fn _get_value(&self, key: &ListKey, index: u128) -> Option<Id> {
+            self.enumerable.get((key, index))
         }
 
-        fn _insert(&mut self, key: &Option<AccountId>, value: &Id) {
-            let mut values = self.enumerable.get(key).unwrap_or_default();
-            values.push(value.clone());
-            self.enumerable.insert(key, &values);
+        fn _insert(&mut self, key: &ListKey, value: &Id) -> Result<(), PSP34Error> {
+            let index = self._count(key);
+            let count = index
+                .checked_add(1)
+                .ok_or(PSP34Error::Custom(String::from(
+                    "Max PSP34 supply exceeded. Max supply limited to 2^128-1.",
+                )))?;
+            self.enumerable.insert((key, index), value);
+            self.indexes.insert((key, value), &index);
+            self.counts.insert(key, &count);
+            Ok(())
         }
 
-        fn _remove(&mut self, key: &Option<AccountId>, value: &Id) {
-            if let Some(mut values) = self.enumerable.get(key) {
-                if let Some(pos) = values.iter().position(|v| v == value) {
-                    values.swap_remove(pos);
-                    self.enumerable.insert(key, &values);
+        fn _remove(&mut self, key: &ListKey, value: &Id) {
+            let Some(index) = self.indexes.take((key, value)) else {
+                return;
+            };
+            let last_index = self._count(key) - 1;
+            if index != last_index {
+                if let Some(last_value) = self.enumerable.get((key, last_index)) {
+                    self.enumerable.insert((key, index), &last_value);
+                    self.indexes.insert((key, &last_value), &index);
                 }
             }
+            self.enumerable.remove((key, last_index));
+            if last_index == 0 {
+                self.counts.remove(key);
+            } else {
+                self.counts.insert(key, &last_index);
+            }
         }
 
-        fn _count(&self, key: &Option<AccountId>) -> u128 {
-            self.enumerable
-                .get(key)
-                .map_or(0, |values| values.len())
-                .try_into()
-                .unwrap()
+        fn _count(&self, key: &ListKey) -> u128 {
+            self.counts.get(key).unwrap_or(0)
         }
 
         pub fn balance_of(&self, owner: &AccountId) -> u32 {
@@ -133,19 +253,22 @@ pub mod balance_manager {
             id: &Id,
             increase_supply: bool,
         ) -> Result<(), PSP34Error> {
-            self._insert(&Some(*owner), id);
+            if self.balance_of(owner) == u32::MAX {
+                return Err(PSP34Error::Custom(String::from(
+                    "Max PSP34 balance exceeded. Max balance limited to 2^32-1.",
+                )));
+            }
+            self._insert(&Some(*owner), id)?;
             if increase_supply {
-                self._insert(&None, id);
+                self._insert(&None, id)?;
+                self.minted.record(id);
             }
 
             Ok(())
         }
 
-        pub fn decrease_balance(&mut self, owner: &AccountId, id: &Id, decrease_supply: bool) {              
+        pub fn decrease_balance(&mut self, owner: &AccountId, id: &Id, decrease_supply: bool) {
             self._remove(&Some(*owner), id);
-            if self.balance_of(owner) == 0 {
-                self.enumerable.remove(Some(owner));
-            }
             if decrease_supply {
                 self._remove(&None, id);
             }
@@ -155,4 +278,4 @@ pub mod balance_manager {
             self._count(&None)
         }
     }
-}
\ No newline at end of file
+}
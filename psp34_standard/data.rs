@@ -1,6 +1,7 @@
 use crate::balances::balance_manager::Balances;
-use crate::PSP34Error;
+use crate::{receiver, PSP34Error};
 use ink::{
+    env::DefaultEnvironment,
     prelude::{string::String, vec, vec::Vec},
     primitives::AccountId,
     storage::Mapping,
@@ -22,6 +23,15 @@ pub enum Id {
     Bytes(Vec<u8>),
 }
 
+/// Account allowed to use a token, without owning it, until `expires`.
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct UserInfo {
+    pub user: AccountId,
+    /// Timestamp (in milliseconds) the usage right ends at, exclusive.
+    pub expires: u64,
+}
+
 /// Temporary type for events emitted during operations that change the
 /// state of PSP34Data struct.
 /// This is meant to be replaced with proper ink! events as soon as the
@@ -43,22 +53,82 @@ pub enum PSP34Event {
         key: Vec<u8>,
         data: Vec<u8>,
     },
+    AttributeRemoved {
+        id: Id,
+        key: Vec<u8>,
+    },
+    /// The user of token `id` changed. A `None` user means it was removed.
+    UpdateUser {
+        id: Id,
+        user: Option<AccountId>,
+        expires: u64,
+    },
+    /// The metadata of token `id` changed.
+    MetadataUpdate {
+        id: Id,
+    },
+    /// The metadata of every token from `from_id` to `to_id` changed.
+    BatchMetadataUpdate {
+        from_id: Id,
+        to_id: Id,
+    },
+    /// Royalty of token `id`, or of the whole collection when `id` is `None`.
+    /// A `None` receiver means the royalty was removed.
+    RoyaltySet {
+        id: Option<Id>,
+        receiver: Option<AccountId>,
+        basis_points: u16,
+    },
 }
 
 #[ink::storage_item]
 #[derive(Debug, Default)]
 pub struct PSP34Data {
     token_owner: Mapping<Id, AccountId>,
-    operator_approvals: Mapping<(AccountId, AccountId, Option<Id>), ()>,
+    operator_approvals: Mapping<(AccountId, AccountId), ()>,
+    token_approvals: Mapping<Id, AccountId>,
     balance: Balances,
     pub last_token_id: u64,
     locked_tokens: Mapping<Id, bool>,
     locked_token_count: u64,
+    all_metadata_frozen: bool,
+    transfer_lockers: Mapping<Id, AccountId>,
+    max_supply: u64,
+    max_mint_per_wallet: Option<u32>,
+    minted_per_wallet: Mapping<AccountId, u32>,
+    canonical_ids: bool,
+    token_users: Mapping<Id, UserInfo>,
+    soulbound: bool,
+    recoverable: bool,
 }
 
 impl PSP34Data {
-    pub fn new() -> PSP34Data {
-        Default::default()
+    /// Creates a collection which can never mint more than `max_supply` tokens.
+    pub fn new(max_supply: u64) -> PSP34Data {
+        PSP34Data {
+            max_supply,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a soulbound collection, whose tokens can not be transferred
+    /// nor approved. With `recoverable` the issuer can still move a token,
+    /// e.g. to a new account of a holder who lost their keys.
+    pub fn new_soulbound(max_supply: u64, recoverable: bool) -> PSP34Data {
+        PSP34Data {
+            max_supply,
+            soulbound: true,
+            recoverable,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_soulbound(&self) -> bool {
+        self.soulbound
+    }
+
+    pub fn is_recoverable(&self) -> bool {
+        self.soulbound && self.recoverable
     }
 
     pub fn total_supply(&self) -> u128 {
@@ -73,11 +143,18 @@ impl PSP34Data {
         self.token_owner.get(id)
     }
 
+    /// Whether `operator` can manage all tokens of `owner`, or token `id` of
+    /// `owner` when given.
     pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<&Id>) -> bool {
-        self.operator_approvals
-            .get((owner, operator, &None))
-            .is_some()
-            || id.is_some() && self.operator_approvals.get((owner, operator, id)).is_some()
+        self.operator_approvals.contains((owner, operator))
+            || id.is_some_and(|id| {
+                self.token_approvals.get(id) == Some(operator) && self.owner_of(id) == Some(owner)
+            })
+    }
+
+    /// Returns the single account approved for token `id`, if any.
+    pub fn get_approved(&self, id: &Id) -> Option<AccountId> {
+        self.token_approvals.get(id)
     }
 
     pub fn collection_id(&self, account_id: AccountId) -> Id {
@@ -91,6 +168,9 @@ impl PSP34Data {
         id: Option<Id>,
         approved: bool,
     ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.soulbound {
+            return Err(PSP34Error::NonTransferable);
+        }
         if let Some(id) = &id {
             let owner = self.owner_of(id).ok_or(PSP34Error::TokenNotExists)?;
             if approved && owner == operator {
@@ -109,12 +189,21 @@ impl PSP34Data {
             caller = owner;
         }
 
-        if approved {
-            self.operator_approvals
-                .insert((caller, operator, id.as_ref()), &());
-        } else {
-            self.operator_approvals
-                .remove((caller, operator, id.as_ref()));
+        match (&id, approved) {
+            // A token has at most one approved account, a new approval
+            // replaces the previous one.
+            (Some(id), true) => {
+                self.token_approvals.insert(id, &operator);
+            }
+            (Some(id), false) => {
+                if self.token_approvals.get(id) == Some(operator) {
+                    self.token_approvals.remove(id);
+                }
+            }
+            (None, true) => {
+                self.operator_approvals.insert((caller, operator), &());
+            }
+            (None, false) => self.operator_approvals.remove((caller, operator)),
         }
 
         Ok(vec![PSP34Event::Approval {
@@ -130,7 +219,7 @@ impl PSP34Data {
         caller: AccountId,
         to: AccountId,
         id: Id,
-        _data: Vec<u8>,
+        data: Vec<u8>,
     ) -> Result<Vec<PSP34Event>, PSP34Error> {
         let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
 
@@ -138,23 +227,64 @@ impl PSP34Data {
             return Ok(vec![]);
         }
 
+        if self.soulbound {
+            return Err(PSP34Error::NonTransferable);
+        }
+
         if owner != caller && !self.allowance(owner, caller, Some(&id)) {
             return Err(PSP34Error::NotApproved);
         }
 
-        self.balance.decrease_balance(&owner, &id, false);
+        let mut events = self._move(owner, to, &id)?;
 
-        self.operator_approvals.remove((owner, caller, Some(&id)));
-        self.token_owner.remove(&id);
+        // A contract transferring a token to itself already knows about it,
+        // and calling it back would be denied as a reentrant call.
+        if to != caller && ink::env::is_contract::<DefaultEnvironment>(&to) {
+            receiver::before_received(caller, owner, to, id.clone(), data)?;
+        }
 
-        self.token_owner.insert(&id, &to);
-        self.balance.increase_balance(&to, &id, false)?;
+        events.push(PSP34Event::Transfer {
+            from: Some(owner),
+            to: Some(to),
+            id,
+        });
+        Ok(events)
+    }
 
-        Ok(vec![PSP34Event::Transfer {
-            from: Some(caller),
+    /// Moves token `id` of a recoverable soulbound collection from its owner
+    /// to `to`. The caller has to check that it is done by the issuer.
+    pub fn recover(&mut self, to: AccountId, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if !self.is_recoverable() {
+            return Err(PSP34Error::NonTransferable);
+        }
+        let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+        if owner == to {
+            return Ok(vec![]);
+        }
+        let mut events = self._move(owner, to, &id)?;
+        events.push(PSP34Event::Transfer {
+            from: Some(owner),
             to: Some(to),
             id,
-        }])
+        });
+        Ok(events)
+    }
+
+    /// Changes the owner of token `id` from `owner` to `to`, dropping its
+    /// approval and user.
+    fn _move(&mut self, owner: AccountId, to: AccountId, id: &Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.is_transfer_locked(id) {
+            return Err(PSP34Error::IsLockedToken);
+        }
+
+        self.balance.decrease_balance(&owner, id, false);
+
+        self.token_approvals.remove(id);
+        self.token_owner.remove(id);
+
+        self.token_owner.insert(id, &to);
+        self.balance.increase_balance(&to, id, false)?;
+        Ok(self.clear_user(id))
     }
 
     pub fn mint(&mut self, account: AccountId, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
@@ -171,53 +301,265 @@ impl PSP34Data {
         }])
     }
 
+    /// Mints the next sequential `Id::U64` token to `account`, respecting the
+    /// collection max supply and the per-wallet mint limit.
+    pub fn mint_next(&mut self, account: AccountId) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.remaining_supply() == 0 {
+            return Err(PSP34Error::MaxSupplyExceeded);
+        }
+        let minted = self.minted_by(account);
+        if let Some(limit) = self.max_mint_per_wallet {
+            if minted >= limit {
+                return Err(PSP34Error::MintLimitExceeded);
+            }
+        }
+        let last_token_id = self
+            .last_token_id
+            .checked_add(1)
+            .ok_or(PSP34Error::Custom(String::from("Cannot increase last token id")))?;
+        let events = self.mint(account, Id::U64(last_token_id))?;
+        self.last_token_id = last_token_id;
+        self.minted_per_wallet.insert(account, &minted.saturating_add(1));
+        Ok(events)
+    }
+
+    /// Burns token `id` owned by `account`. The `caller` has to be the owner
+    /// of the token or an operator approved for it.
     pub fn burn(
         &mut self,
         caller: AccountId,
         account: AccountId,
         id: Id,
     ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        if self.owner_of(&id).is_none() {
-            return Err(PSP34Error::TokenNotExists);
+        let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+        if owner != account {
+            return Err(PSP34Error::NotTokenOwner);
         }
-        if account != caller && !self.allowance(caller, account, None) {
+        if owner != caller && !self.allowance(owner, caller, Some(&id)) {
             return Err(PSP34Error::NotApproved);
         }
-        self.balance.decrease_balance(&account, &id, true);
+        if self.is_transfer_locked(&id) {
+            return Err(PSP34Error::IsLockedToken);
+        }
+        self.token_approvals.remove(&id);
+        self.balance.decrease_balance(&owner, &id, true);
         self.token_owner.remove(&id);
+        let mut events = self.clear_user(&id);
 
-        Ok(vec![PSP34Event::Transfer {
+        events.push(PSP34Event::Transfer {
             from: Some(account),
             to: None,
             id,
-        }])
+        });
+        Ok(events)
+    }
+
+    /// Lets `user` use token `id` until timestamp `expires`, or removes the
+    /// current user when `user` is `None`. The caller has to be the owner of
+    /// the token or an operator approved for it. Tokens of a soulbound
+    /// collection can not be lent.
+    pub fn set_user(
+        &mut self,
+        caller: AccountId,
+        id: Id,
+        user: Option<AccountId>,
+        expires: u64,
+        now: u64,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.soulbound {
+            return Err(PSP34Error::NonTransferable);
+        }
+        let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+        if owner != caller && !self.allowance(owner, caller, Some(&id)) {
+            return Err(PSP34Error::NotApproved);
+        }
+        match user {
+            Some(user) => {
+                if expires <= now {
+                    return Err(PSP34Error::InvalidInput);
+                }
+                self.token_users.insert(&id, &UserInfo { user, expires });
+            }
+            None => {
+                self.token_users.remove(&id);
+            }
+        }
+        let expires = if user.is_some() { expires } else { 0 };
+        Ok(vec![PSP34Event::UpdateUser { id, user, expires }])
+    }
+
+    /// Returns the user of token `id` at timestamp `now`, if any.
+    pub fn user_of(&self, id: &Id, now: u64) -> Option<AccountId> {
+        self.token_users
+            .get(id)
+            .filter(|info| now < info.expires)
+            .map(|info| info.user)
+    }
+
+    /// Returns the timestamp the current user of token `id` can use it until,
+    /// or 0 when it has none.
+    pub fn user_expires(&self, id: &Id) -> u64 {
+        self.token_users.get(id).map_or(0, |info| info.expires)
+    }
+
+    /// Removes the user of token `id`, which does not carry over to a new owner.
+    fn clear_user(&mut self, id: &Id) -> Vec<PSP34Event> {
+        match self.token_users.take(id) {
+            Some(_) => vec![PSP34Event::UpdateUser {
+                id: id.clone(),
+                user: None,
+                expires: 0,
+            }],
+            None => vec![],
+        }
+    }
+
+    /// Whether ids are normalized with `Id::canonical` before being used.
+    pub fn canonical_ids(&self) -> bool {
+        self.canonical_ids
+    }
+
+    pub fn set_canonical_ids(&mut self, enabled: bool) {
+        self.canonical_ids = enabled;
+    }
+
+    /// Returns the canonical form of `id` when canonical ids are enabled,
+    /// so that e.g. `Id::U8(1)` and `Id::U64(1)` refer to the same token.
+    pub fn normalize_id(&self, id: Id) -> Id {
+        if self.canonical_ids {
+            id.canonical()
+        } else {
+            id
+        }
+    }
+
+    /// Whether `id` is one of the ids the collection can mint.
+    pub fn is_mintable_id(&self, id: &Id) -> bool {
+        matches!(id, Id::U64(val) if (1..=self.max_supply).contains(val))
     }
 
     pub fn get_last_token_id(&self) -> u64 {
         self.last_token_id
     }
 
+    pub fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    /// Number of tokens that can still be minted. Burned tokens do not free
+    /// up supply, since their ids are never reused.
+    pub fn remaining_supply(&self) -> u64 {
+        self.max_supply.saturating_sub(self.last_token_id)
+    }
+
+    /// Lowers the max supply. It can never be raised, nor set below the
+    /// number of tokens minted so far.
+    pub fn set_max_supply(&mut self, max_supply: u64) -> Result<(), PSP34Error> {
+        if max_supply > self.max_supply || max_supply < self.last_token_id {
+            return Err(PSP34Error::InvalidInput);
+        }
+        self.max_supply = max_supply;
+        Ok(())
+    }
+
+    pub fn max_mint_per_wallet(&self) -> Option<u32> {
+        self.max_mint_per_wallet
+    }
+
+    pub fn set_max_mint_per_wallet(&mut self, limit: Option<u32>) {
+        self.max_mint_per_wallet = limit;
+    }
+
+    /// Number of tokens minted to `account` so far.
+    pub fn minted_by(&self, account: AccountId) -> u32 {
+        self.minted_per_wallet.get(account).unwrap_or(0)
+    }
+
+    /// Permanently freezes the metadata of `token_id`.
     pub fn lock(&mut self, token_id: Id) -> Result<(), PSP34Error> {
-        if let Some(locked_token_count) = self.locked_token_count.checked_add(1) {
-            self.locked_token_count = locked_token_count;
-            self.locked_tokens.insert(&token_id, &true);
+        if self.locked_tokens.contains(&token_id) {
             return Ok(());
-        } else {
-            return Err(PSP34Error::Custom(String::from("Cannot increase locked token count")));
         }
+        self.locked_token_count = self
+            .locked_token_count
+            .checked_add(1)
+            .ok_or(PSP34Error::Custom(String::from("Cannot increase locked token count")))?;
+        self.locked_tokens.insert(&token_id, &true);
+        Ok(())
     }
-    
+
+    /// Whether the metadata of `token_id` is frozen, either on its own or
+    /// with the whole collection.
     pub fn is_locked_nft(&self, token_id: Id) -> bool {
-        if self.locked_tokens.get(&token_id).is_some() {
-            return true;
-        }
-        return false;
+        self.all_metadata_frozen || self.locked_tokens.contains(&token_id)
     }
-    
+
     pub fn get_locked_token_count(&self) -> u64 {
         self.locked_token_count
     }
 
+    /// Permanently freezes the metadata of the whole collection.
+    pub fn freeze_all_metadata(&mut self) {
+        self.all_metadata_frozen = true;
+    }
+
+    pub fn is_all_metadata_frozen(&self) -> bool {
+        self.all_metadata_frozen
+    }
+
+    /// Prevents token `id` from being transferred or burned until `caller`
+    /// unlocks it. The caller has to be the owner of the token or an operator
+    /// approved for all its tokens, e.g. a staking contract. An approval for
+    /// the token alone, such as a marketplace listing, is not enough.
+    pub fn lock_transfer(&mut self, caller: AccountId, id: &Id) -> Result<(), PSP34Error> {
+        let owner = self.owner_of(id).ok_or(PSP34Error::TokenNotExists)?;
+        if owner != caller && !self.allowance(owner, caller, None) {
+            return Err(PSP34Error::NotApproved);
+        }
+        if self.is_transfer_locked(id) {
+            return Err(PSP34Error::IsLockedToken);
+        }
+        self.transfer_lockers.insert(id, &caller);
+        Ok(())
+    }
+
+    /// Lifts the transfer lock of token `id`. Only the account which locked
+    /// the token can unlock it.
+    pub fn unlock_transfer(&mut self, caller: AccountId, id: &Id) -> Result<(), PSP34Error> {
+        if self.transfer_locker(id) != Some(caller) {
+            return Err(PSP34Error::NotApproved);
+        }
+        self.transfer_lockers.remove(id);
+        Ok(())
+    }
+
+    /// Returns the account which locked transfers of token `id`, if any.
+    pub fn transfer_locker(&self, id: &Id) -> Option<AccountId> {
+        self.transfer_lockers.get(id)
+    }
+
+    pub fn is_transfer_locked(&self, id: &Id) -> bool {
+        self.transfer_lockers.contains(id)
+    }
+
+    pub fn tokens_of_owner(&self, owner: AccountId, offset: u128, limit: u32) -> Vec<Id> {
+        self.balance.tokens_of_owner(owner, offset, limit)
+    }
+
+    /// Returns the tokens minted at positions `offset` to `offset + limit`
+    /// which were not burned since.
+    pub fn all_tokens(&self, offset: u128, limit: u32) -> Vec<Id> {
+        self.balance
+            .minted_tokens(offset, limit)
+            .into_iter()
+            .filter(|id| self.token_owner.contains(id))
+            .collect()
+    }
+
+    pub fn minted_count(&self) -> u128 {
+        self.balance.minted_count()
+    }
+
     #[cfg(feature = "enumerable")]
     pub fn owners_token_by_index(&self, owner: AccountId, index: u128) -> Result<Id, PSP34Error> {
         self.balance.owners_token_by_index(owner, index)
@@ -235,15 +577,85 @@ impl Default for Id {
     }
 }
 
-impl From<Id> for u128 {
-    fn from(id: Id) -> Self {
+impl Id {
+    /// Returns the canonical form of the id: integer ids which fit in a `u64`
+    /// become `Id::U64`, the variant minted by the contract. `Id::Bytes` and
+    /// larger `Id::U128` values are returned unchanged.
+    pub fn canonical(self) -> Id {
+        match self {
+            Id::U8(val) => Id::U64(val as u64),
+            Id::U16(val) => Id::U64(val as u64),
+            Id::U32(val) => Id::U64(val as u64),
+            Id::U128(val) => u64::try_from(val).map_or(Id::U128(val), Id::U64),
+            id => id,
+        }
+    }
+}
+
+impl TryFrom<Id> for u128 {
+    type Error = PSP34Error;
+
+    /// Fails for `Id::Bytes` which are not exactly 16 bytes long. Those are
+    /// read as a big endian number.
+    fn try_from(id: Id) -> Result<Self, Self::Error> {
         match id {
-            Id::U8(val) => val as u128,
-            Id::U16(val) => val as u128,
-            Id::U32(val) => val as u128,
-            Id::U64(val) => val as u128,
-            Id::U128(val) => val,
-            Id::Bytes(val) => u128::from_be_bytes(val.as_slice().try_into().unwrap()),
+            Id::U8(val) => Ok(val as u128),
+            Id::U16(val) => Ok(val as u128),
+            Id::U32(val) => Ok(val as u128),
+            Id::U64(val) => Ok(val as u128),
+            Id::U128(val) => Ok(val),
+            Id::Bytes(val) => <[u8; 16]>::try_from(val.as_slice())
+                .map(u128::from_be_bytes)
+                .map_err(|_| PSP34Error::InvalidInput),
+        }
+    }
+}
+
+/// Formats the id as `<variant>:<value>`, e.g. `u64:42` or `bytes:0x2a00`,
+/// which `FromStr` parses back into the same variant.
+#[cfg(feature = "std")]
+impl core::fmt::Display for Id {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Id::U8(val) => write!(f, "u8:{}", val),
+            Id::U16(val) => write!(f, "u16:{}", val),
+            Id::U32(val) => write!(f, "u32:{}", val),
+            Id::U64(val) => write!(f, "u64:{}", val),
+            Id::U128(val) => write!(f, "u128:{}", val),
+            Id::Bytes(val) => {
+                write!(f, "bytes:0x")?;
+                val.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Parses ids formatted by `Display`. A plain number without a variant is
+/// read as `Id::U64`.
+#[cfg(feature = "std")]
+impl core::str::FromStr for Id {
+    type Err = PSP34Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (variant, value) = s.split_once(':').unwrap_or(("u64", s));
+        let id = match variant {
+            "u8" => value.parse().map(Id::U8).ok(),
+            "u16" => value.parse().map(Id::U16).ok(),
+            "u32" => value.parse().map(Id::U32).ok(),
+            "u64" => value.parse().map(Id::U64).ok(),
+            "u128" => value.parse().map(Id::U128).ok(),
+            "bytes" => value
+                .strip_prefix("0x")
+                .filter(|hex| hex.len() % 2 == 0)
+                .and_then(|hex| {
+                    (0..hex.len())
+                        .step_by(2)
+                        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                        .collect::<Option<Vec<u8>>>()
+                })
+                .map(Id::Bytes),
+            _ => None,
+        };
+        id.ok_or(PSP34Error::InvalidInput)
+    }
+}
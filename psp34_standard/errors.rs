@@ -24,5 +24,45 @@ pub enum PSP34Error {
     NotEnoughBalance,
     NotTokenOwner,
     IsLockedToken,
-    InvalidInput
+    InvalidInput,
+    /// Returned if minting would exceed the collection max supply
+    MaxSupplyExceeded,
+    /// Returned if the account already minted the maximum allowed number of tokens
+    MintLimitExceeded,
+    /// Returned if no mint phase is open
+    NoActiveMintPhase,
+    /// Returned if minting would exceed the cap of the mint phase
+    PhaseCapExceeded,
+    /// Returned if the transferred value does not match the mint price
+    InvalidFee,
+    /// Returned if the account is not on the allowlist of the mint phase
+    NotAllowlisted,
+    /// Returned if the reveal state can not be changed anymore
+    AlreadyRevealed,
+    /// Returned if the collection is revealed before committing to a provenance hash
+    ProvenanceHashNotSet,
+    /// Returned if a provenance hash is already committed
+    ProvenanceHashAlreadySet,
+    /// Returned if the provenance hash is committed after the first mint
+    MintingStarted,
+    /// Returned if the metadata of the collection is frozen
+    MetadataFrozen,
+    /// Returned if tokens of a soulbound collection would change hands
+    NonTransferable,
+}
+
+/// Error returned by a contract to reject an incoming token.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34ReceiverError {
+    /// Returned if the receiver does not accept the token
+    TransferRejected(String),
+}
+
+impl From<PSP34ReceiverError> for PSP34Error {
+    fn from(error: PSP34ReceiverError) -> Self {
+        match error {
+            PSP34ReceiverError::TransferRejected(reason) => PSP34Error::SafeTransferCheckFailed(reason),
+        }
+    }
 }
@@ -0,0 +1,226 @@
+use crate::{merkle, PSP34Error};
+use ink::{
+    prelude::string::String,
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+/// A time window in which tokens can be minted for a fixed price.
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct MintPhase {
+    /// Timestamp (in milliseconds) the phase opens at.
+    pub start_time: u64,
+    /// Timestamp (in milliseconds) the phase closes at, exclusive.
+    pub end_time: u64,
+    /// Price of a single token.
+    pub price: u128,
+    /// Maximum number of tokens minted during the phase.
+    pub cap: u64,
+    /// Maximum number of tokens a single account can mint during the phase.
+    pub max_per_wallet: Option<u32>,
+    /// Number of tokens minted during the phase so far.
+    pub minted: u64,
+    /// Root of the allowlist tree. Only allowlisted accounts can mint in the
+    /// phase when it is set.
+    pub merkle_root: Option<merkle::Hash>,
+}
+
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct LaunchpadData {
+    phases: Mapping<u32, MintPhase>,
+    phase_count: u32,
+    minted_in_phase: Mapping<(u32, AccountId), u32>,
+}
+
+impl LaunchpadData {
+    pub fn new() -> LaunchpadData {
+        Default::default()
+    }
+
+    pub fn get_phase(&self, phase_id: u32) -> Option<MintPhase> {
+        self.phases.get(phase_id)
+    }
+
+    pub fn get_phase_count(&self) -> u32 {
+        self.phase_count
+    }
+
+    pub fn get_minted_in_phase(&self, phase_id: u32, account: AccountId) -> u32 {
+        self.minted_in_phase.get((phase_id, account)).unwrap_or(0)
+    }
+
+    /// Returns the phase open at `timestamp`, if any.
+    pub fn active_phase(&self, timestamp: u64) -> Option<u32> {
+        (1..=self.phase_count).find(|phase_id| {
+            self.phases
+                .get(phase_id)
+                .is_some_and(|phase| phase.start_time <= timestamp && timestamp < phase.end_time)
+        })
+    }
+
+    /// Adds a new phase and returns its id. Phases can not overlap in time.
+    pub fn add_phase(
+        &mut self,
+        start_time: u64,
+        end_time: u64,
+        price: u128,
+        cap: u64,
+        max_per_wallet: Option<u32>,
+    ) -> Result<u32, PSP34Error> {
+        self.check_window(None, start_time, end_time)?;
+        let phase_id = self
+            .phase_count
+            .checked_add(1)
+            .ok_or(PSP34Error::Custom(String::from("Cannot increase phase count")))?;
+        self.phases.insert(
+            phase_id,
+            &MintPhase {
+                start_time,
+                end_time,
+                price,
+                cap,
+                max_per_wallet,
+                minted: 0,
+                merkle_root: None,
+            },
+        );
+        self.phase_count = phase_id;
+        Ok(phase_id)
+    }
+
+    /// Changes an existing phase. Tokens already minted in it are kept, so the
+    /// new cap can not be lower than that.
+    pub fn update_phase(
+        &mut self,
+        phase_id: u32,
+        start_time: u64,
+        end_time: u64,
+        price: u128,
+        cap: u64,
+        max_per_wallet: Option<u32>,
+    ) -> Result<(), PSP34Error> {
+        let mut phase = self.phases.get(phase_id).ok_or(PSP34Error::InvalidInput)?;
+        self.check_window(Some(phase_id), start_time, end_time)?;
+        if cap < phase.minted {
+            return Err(PSP34Error::InvalidInput);
+        }
+        phase.start_time = start_time;
+        phase.end_time = end_time;
+        phase.price = price;
+        phase.cap = cap;
+        phase.max_per_wallet = max_per_wallet;
+        self.phases.insert(phase_id, &phase);
+        Ok(())
+    }
+
+    /// Turns phase `phase_id` into an allowlist phase, or back into a public
+    /// one when `merkle_root` is `None`.
+    pub fn set_merkle_root(
+        &mut self,
+        phase_id: u32,
+        merkle_root: Option<merkle::Hash>,
+    ) -> Result<(), PSP34Error> {
+        let mut phase = self.phases.get(phase_id).ok_or(PSP34Error::InvalidInput)?;
+        phase.merkle_root = merkle_root;
+        self.phases.insert(phase_id, &phase);
+        Ok(())
+    }
+
+    /// Records `amount` tokens minted by `account` in the public phase
+    /// `phase_id` and returns the total price to be paid for them.
+    pub fn record_mint(
+        &mut self,
+        phase_id: u32,
+        account: AccountId,
+        amount: u32,
+    ) -> Result<u128, PSP34Error> {
+        let phase = self.phases.get(phase_id).ok_or(PSP34Error::NoActiveMintPhase)?;
+        if phase.merkle_root.is_some() {
+            return Err(PSP34Error::NotAllowlisted);
+        }
+        self._record_mint(phase, phase_id, account, amount)
+    }
+
+    /// Records `amount` tokens minted by `account` in the allowlist phase
+    /// `phase_id` and returns the total price to be paid for them.
+    /// `proof` has to show that `(account, max_quantity)` is a leaf of the
+    /// phase allowlist tree, and `account` can mint at most `max_quantity`
+    /// tokens in the phase.
+    pub fn record_allowlist_mint(
+        &mut self,
+        phase_id: u32,
+        account: AccountId,
+        amount: u32,
+        max_quantity: u32,
+        proof: &[merkle::Hash],
+    ) -> Result<u128, PSP34Error> {
+        let phase = self.phases.get(phase_id).ok_or(PSP34Error::NoActiveMintPhase)?;
+        let root = phase.merkle_root.ok_or(PSP34Error::NotAllowlisted)?;
+        if !merkle::verify(&root, merkle::leaf(account, max_quantity), proof) {
+            return Err(PSP34Error::NotAllowlisted);
+        }
+        if self.get_minted_in_phase(phase_id, account).saturating_add(amount) > max_quantity {
+            return Err(PSP34Error::MintLimitExceeded);
+        }
+        self._record_mint(phase, phase_id, account, amount)
+    }
+
+    fn _record_mint(
+        &mut self,
+        mut phase: MintPhase,
+        phase_id: u32,
+        account: AccountId,
+        amount: u32,
+    ) -> Result<u128, PSP34Error> {
+        if amount == 0 {
+            return Err(PSP34Error::InvalidInput);
+        }
+        let minted = phase
+            .minted
+            .checked_add(amount as u64)
+            .filter(|minted| *minted <= phase.cap)
+            .ok_or(PSP34Error::PhaseCapExceeded)?;
+        let minted_by_account = self
+            .get_minted_in_phase(phase_id, account)
+            .checked_add(amount)
+            .ok_or(PSP34Error::MintLimitExceeded)?;
+        if let Some(limit) = phase.max_per_wallet {
+            if minted_by_account > limit {
+                return Err(PSP34Error::MintLimitExceeded);
+            }
+        }
+        let price = phase
+            .price
+            .checked_mul(amount as u128)
+            .ok_or(PSP34Error::InvalidFee)?;
+
+        phase.minted = minted;
+        self.phases.insert(phase_id, &phase);
+        self.minted_in_phase.insert((phase_id, account), &minted_by_account);
+        Ok(price)
+    }
+
+    fn check_window(
+        &self,
+        phase_id: Option<u32>,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<(), PSP34Error> {
+        if start_time >= end_time {
+            return Err(PSP34Error::InvalidInput);
+        }
+        let overlaps = (1..=self.phase_count)
+            .filter(|other| Some(*other) != phase_id)
+            .filter_map(|other| self.phases.get(other))
+            .any(|other| start_time < other.end_time && other.start_time < end_time);
+        if overlaps {
+            return Err(PSP34Error::InvalidInput);
+        }
+        Ok(())
+    }
+}
@@ -3,23 +3,30 @@
 mod balances;
 mod data;
 mod errors;
+mod launchpad;
+pub mod merkle;
 pub mod metadata;
+pub mod receiver;
 mod traits;
 mod unit_tests;
 mod ownable;
+mod royalty;
 
-pub use data::{Id, PSP34Data, PSP34Event};
-pub use errors::PSP34Error;
-pub use traits::{PSP34Metadata, PSP34, PSP34Traits, Ownable};
+pub use data::{Id, PSP34Data, PSP34Event, UserInfo};
+pub use errors::{PSP34Error, PSP34ReceiverError};
+pub use traits::{PSP34Burnable, PSP34Capped, PSP34CollectionMetadata, PSP34Launchpad, PSP34Listing, PSP34Metadata, PSP34Receiver, PSP34Rental, PSP34Reveal, PSP34Royalty, PSP34Soulbound, PSP34, PSP34Traits, PSP34TransferLock, Ownable, AdminTrait};
 pub use ownable::OwnableData;
+pub use launchpad::{LaunchpadData, MintPhase};
+pub use royalty::{RoyaltyData, BASIS_POINTS_DENOMINATOR};
 
 #[cfg(feature = "enumerable")]
 pub use traits::PSP34Enumerable;
 
 #[cfg(feature = "contract")]
 #[ink::contract]
-mod token {
+pub mod token {
     use crate::{
+        merkle,
         metadata, 
         Id, 
         PSP34Data, 
@@ -27,11 +34,27 @@ mod token {
         PSP34Event, 
         PSP34Metadata, 
         PSP34,
+        PSP34Burnable,
+        PSP34Capped,
+        PSP34CollectionMetadata,
+        PSP34Launchpad,
+        PSP34Listing,
+        PSP34Rental,
+        PSP34Reveal,
+        PSP34Royalty,
+        PSP34Soulbound,
         PSP34Traits,
+        PSP34TransferLock,
         Ownable,
         OwnableData,
+        AdminTrait,
+        LaunchpadData,
+        MintPhase,
+        RoyaltyData,
     };
     use ink::prelude::{string::String, vec::Vec};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::codegen::EmitEvent;
 
     #[cfg(feature = "enumerable")]
     use crate::PSP34Enumerable;
@@ -40,19 +63,50 @@ mod token {
     pub struct Token {
         data: PSP34Data,
         metadata: metadata::Data,
-        ownable_data: OwnableData
+        ownable_data: OwnableData,
+        launchpad: LaunchpadData,
+        royalty: RoyaltyData,
     }
 
     impl Token {
         #[ink(constructor)]
-        pub fn new(contract_owner: AccountId) -> Self {
+        pub fn new(
+            contract_owner: AccountId,
+            max_supply: u64,
+            name: Option<String>,
+            symbol: Option<String>,
+        ) -> Self {
+            let mut metadata = metadata::Data::default();
+            metadata.set_collection_metadata(metadata::CollectionMetadata {
+                name,
+                symbol,
+                ..Default::default()
+            });
             Self {
-                data: PSP34Data::new(),
-                metadata: metadata::Data::default(),
-                ownable_data: OwnableData::new(Some(contract_owner))
+                data: PSP34Data::new(max_supply),
+                metadata,
+                ownable_data: OwnableData::new(Some(contract_owner)),
+                launchpad: LaunchpadData::new(),
+                royalty: RoyaltyData::new(),
             }
         }
 
+        /// Creates a soulbound collection: its tokens can only be minted, and
+        /// burned by their holder or the owner of the collection. With
+        /// `recoverable` the owner can move a token to another account.
+        #[ink(constructor)]
+        pub fn new_soulbound(
+            contract_owner: AccountId,
+            max_supply: u64,
+            name: Option<String>,
+            symbol: Option<String>,
+            recoverable: bool,
+        ) -> Self {
+            let mut token = Self::new(contract_owner, max_supply, name, symbol);
+            token.data = PSP34Data::new_soulbound(max_supply, recoverable);
+            token
+        }
+
         fn emit_events(&self, events: ink::prelude::vec::Vec<PSP34Event>) {
             for event in events {
                 match event {
@@ -61,37 +115,64 @@ mod token {
                         operator,
                         id,
                         approved,
-                    } => self.env().emit_event(Approval {
+                    } => EmitEvent::<Token>::emit_event(self.env(), Approval {
                         owner,
                         operator,
                         id,
                         approved,
                     }),
                     PSP34Event::Transfer { from, to, id } => {
-                        self.env().emit_event(Transfer { from, to, id })
+                        EmitEvent::<Token>::emit_event(self.env(), Transfer { from, to, id })
                     }
                     PSP34Event::AttributeSet { id, key, data } => {
-                        self.env().emit_event(AttributeSet { id, key, data })
+                        EmitEvent::<Token>::emit_event(self.env(), AttributeSet { id, key, data })
+                    }
+                    PSP34Event::AttributeRemoved { id, key } => {
+                        EmitEvent::<Token>::emit_event(self.env(), AttributeRemoved { id, key })
+                    }
+                    PSP34Event::UpdateUser { id, user, expires } => {
+                        EmitEvent::<Token>::emit_event(self.env(), UpdateUser { id, user, expires })
+                    }
+                    PSP34Event::MetadataUpdate { id } => {
+                        EmitEvent::<Token>::emit_event(self.env(), MetadataUpdate { id })
+                    }
+                    PSP34Event::BatchMetadataUpdate { from_id, to_id } => {
+                        EmitEvent::<Token>::emit_event(self.env(), BatchMetadataUpdate { from_id, to_id })
                     }
+                    PSP34Event::RoyaltySet {
+                        id,
+                        receiver,
+                        basis_points,
+                    } => EmitEvent::<Token>::emit_event(self.env(), RoyaltySet {
+                        id,
+                        receiver,
+                        basis_points,
+                    }),
                 }
             }
         }
 
+        /// Mints `amount` tokens to `to` once `price` has been transferred.
+        fn paid_mint(&mut self, to: AccountId, amount: u32, price: u128) -> Result<(), PSP34Error> {
+            if self.env().transferred_value() != price {
+                return Err(PSP34Error::InvalidFee)
+            }
+            for _ in 0..amount {
+                let events = self.data.mint_next(to)?;
+                self.emit_events(events);
+            }
+            Ok(())
+        }
+
         #[ink(message)]
         pub fn mint(&mut self) -> Result<(), PSP34Error> {
             let caller = self.env().caller();
             if self.ownable_data.owner() != Some(caller) {
                 return Err(PSP34Error::CallerIsNotOwner)
             }
-            if let Some(last_token_id) = self.data.last_token_id.checked_add(1) {
-                self.data.last_token_id = last_token_id;
-                if self.data.mint(caller, Id::U64(self.data.last_token_id)).is_err(){
-                    return Err(PSP34Error::Custom(String::from("Cannot mint")));
-                }
-                return Ok(());
-            } else {
-                return Err(PSP34Error::Custom(String::from("Cannot increase last token id")));
-            }
+            let events = self.data.mint_next(caller)?;
+            self.emit_events(events);
+            Ok(())
         }
 
         #[ink(message)]
@@ -100,18 +181,69 @@ mod token {
             if self.ownable_data.owner() != Some(caller) {
                 return Err(PSP34Error::CallerIsNotOwner)
             }
-            if let Some(last_token_id) = self.data.last_token_id.checked_add(1) {
-                self.data.last_token_id = last_token_id;
-                if self.data.mint(caller, Id::U64(self.data.last_token_id)).is_err(){
-                    return Err(PSP34Error::Custom(String::from("Cannot mint")));
-                }
-                if self.metadata.set_multiple_attributes(Id::U64(self.data.last_token_id), metadata).is_err(){
-                    return Err(PSP34Error::Custom(String::from("Cannot set attributes")));
-                }
-                return Ok(());
-            } else {
-                return Err(PSP34Error::Custom(String::from("Cannot increase last token id")));
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let events = self.data.mint_next(caller)?;
+            self.emit_events(events);
+            let events = self
+                .metadata
+                .set_multiple_attributes(Id::U64(self.data.last_token_id), metadata)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mint_to(&mut self, recipient: AccountId) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.data.mint_next(recipient)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Mints one token to each of `recipients`, with consecutive ids.
+        #[ink(message)]
+        pub fn batch_mint_to(&mut self, recipients: Vec<AccountId>) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
             }
+            if recipients.is_empty() {
+                return Err(PSP34Error::InvalidInput)
+            }
+            for recipient in recipients {
+                let events = self.data.mint_next(recipient)?;
+                self.emit_events(events);
+            }
+            Ok(())
+        }
+
+        /// Mints one token with the given attributes to each recipient, with
+        /// consecutive ids.
+        #[ink(message)]
+        pub fn batch_mint_with_attributes(
+            &mut self,
+            tokens: Vec<(AccountId, Vec<(String, String)>)>
+        ) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if tokens.is_empty() {
+                return Err(PSP34Error::InvalidInput)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            for (recipient, metadata) in tokens {
+                let events = self.data.mint_next(recipient)?;
+                self.emit_events(events);
+                let events = self
+                    .metadata
+                    .set_multiple_attributes(Id::U64(self.data.last_token_id), metadata)?;
+                self.emit_events(events);
+            }
+            Ok(())
         }
     }
 
@@ -143,6 +275,45 @@ mod token {
         data: Vec<u8>,
     }
 
+    #[ink(event)]
+    pub struct AttributeRemoved {
+        id: Id,
+        key: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct UpdateUser {
+        #[ink(topic)]
+        id: Id,
+        #[ink(topic)]
+        user: Option<AccountId>,
+        expires: u64,
+    }
+
+    /// Tells indexers to refresh the metadata of token `id`.
+    #[ink(event)]
+    pub struct MetadataUpdate {
+        #[ink(topic)]
+        id: Id,
+    }
+
+    /// Tells indexers to refresh the metadata of every token from `from_id`
+    /// to `to_id`.
+    #[ink(event)]
+    pub struct BatchMetadataUpdate {
+        from_id: Id,
+        to_id: Id,
+    }
+
+    #[ink(event)]
+    pub struct RoyaltySet {
+        #[ink(topic)]
+        id: Option<Id>,
+        #[ink(topic)]
+        receiver: Option<AccountId>,
+        basis_points: u16,
+    }
+
     impl PSP34 for Token {
         #[ink(message)]
         fn collection_id(&self) -> Id {
@@ -161,6 +332,7 @@ mod token {
 
         #[ink(message)]
         fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
+            let id = id.map(|id| self.data.normalize_id(id));
             self.data.allowance(owner, operator, id.as_ref())
         }
 
@@ -171,6 +343,7 @@ mod token {
             id: Id,
             data: ink::prelude::vec::Vec<u8>,
         ) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
             let events = self.data.transfer(self.env().caller(), to, id, data)?;
             self.emit_events(events);
             Ok(())
@@ -183,6 +356,7 @@ mod token {
             id: Option<Id>,
             approved: bool,
         ) -> Result<(), PSP34Error> {
+            let id = id.map(|id| self.data.normalize_id(id));
             let events = self
                 .data
                 .approve(self.env().caller(), operator, id, approved)?;
@@ -192,10 +366,366 @@ mod token {
 
         #[ink(message)]
         fn owner_of(&self, id: Id) -> Option<AccountId> {
+            let id = self.data.normalize_id(id);
             self.data.owner_of(&id)
         }
     }
 
+    impl PSP34Burnable for Token {
+        #[ink(message)]
+        fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            let mut caller = self.env().caller();
+            // The issuer of a soulbound collection can revoke any token.
+            if self.data.is_soulbound() && self.ownable_data.owner() == Some(caller) {
+                caller = account;
+            }
+            let events = self.data.burn(caller, account, id.clone())?;
+            self.metadata.clear_attributes(&id);
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Capped for Token {
+        #[ink(message)]
+        fn max_supply(&self) -> u64 {
+            self.data.max_supply()
+        }
+
+        #[ink(message)]
+        fn remaining_supply(&self) -> u64 {
+            self.data.remaining_supply()
+        }
+
+        #[ink(message)]
+        fn set_max_supply(&mut self, max_supply: u64) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.data.set_max_supply(max_supply)
+        }
+
+        #[ink(message)]
+        fn max_mint_per_wallet(&self) -> Option<u32> {
+            self.data.max_mint_per_wallet()
+        }
+
+        #[ink(message)]
+        fn set_max_mint_per_wallet(&mut self, limit: Option<u32>) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.data.set_max_mint_per_wallet(limit);
+            Ok(())
+        }
+    }
+
+    impl PSP34Launchpad for Token {
+        #[ink(message)]
+        fn add_mint_phase(
+            &mut self,
+            start_time: u64,
+            end_time: u64,
+            price: u128,
+            cap: u64,
+            max_per_wallet: Option<u32>,
+        ) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.launchpad.add_phase(start_time, end_time, price, cap, max_per_wallet)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn update_mint_phase(
+            &mut self,
+            phase_id: u32,
+            start_time: u64,
+            end_time: u64,
+            price: u128,
+            cap: u64,
+            max_per_wallet: Option<u32>,
+        ) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.launchpad.update_phase(phase_id, start_time, end_time, price, cap, max_per_wallet)
+        }
+
+        #[ink(message)]
+        fn get_mint_phase(&self, phase_id: u32) -> Option<MintPhase> {
+            self.launchpad.get_phase(phase_id)
+        }
+
+        #[ink(message)]
+        fn get_mint_phase_count(&self) -> u32 {
+            self.launchpad.get_phase_count()
+        }
+
+        #[ink(message)]
+        fn get_active_mint_phase(&self) -> Option<u32> {
+            self.launchpad.active_phase(self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn get_minted_in_phase(&self, phase_id: u32, account: AccountId) -> u32 {
+            self.launchpad.get_minted_in_phase(phase_id, account)
+        }
+
+        #[ink(message)]
+        fn set_mint_phase_merkle_root(
+            &mut self,
+            phase_id: u32,
+            merkle_root: Option<merkle::Hash>,
+        ) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.launchpad.set_merkle_root(phase_id, merkle_root)
+        }
+
+        #[ink(message, payable)]
+        fn public_mint(&mut self, amount: u32) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            let phase_id = self
+                .get_active_mint_phase()
+                .ok_or(PSP34Error::NoActiveMintPhase)?;
+            let price = self.launchpad.record_mint(phase_id, caller, amount)?;
+            self.paid_mint(caller, amount, price)
+        }
+
+        #[ink(message, payable)]
+        fn allowlist_mint(
+            &mut self,
+            amount: u32,
+            max_quantity: u32,
+            proof: Vec<merkle::Hash>,
+        ) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            let phase_id = self
+                .get_active_mint_phase()
+                .ok_or(PSP34Error::NoActiveMintPhase)?;
+            let price = self
+                .launchpad
+                .record_allowlist_mint(phase_id, caller, amount, max_quantity, &proof)?;
+            self.paid_mint(caller, amount, price)
+        }
+    }
+
+    impl PSP34Reveal for Token {
+        #[ink(message)]
+        fn set_pre_reveal_uri(&mut self, uri: Option<String>) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let events = self.metadata.set_pre_reveal_uri(uri)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_pre_reveal_uri(&self) -> Option<String> {
+            self.metadata.get_pre_reveal_uri()
+        }
+
+        #[ink(message)]
+        fn set_provenance_hash(&mut self, provenance_hash: [u8; 32]) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let minting_started = self.data.get_last_token_id() > 0 || self.data.total_supply() > 0;
+            self.metadata.set_provenance_hash(provenance_hash, minting_started)
+        }
+
+        #[ink(message)]
+        fn get_provenance_hash(&self) -> Option<[u8; 32]> {
+            self.metadata.get_provenance_hash()
+        }
+
+        #[ink(message)]
+        fn reveal(&mut self, base_uri: String, offset_seed: u64) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            if self.metadata.is_revealed() {
+                return Err(PSP34Error::AlreadyRevealed)
+            }
+            // The metadata order is fixed by the provenance hash before the
+            // first mint, and the reveal block was unknown when it was
+            // committed. The owner still chooses the seed and when to reveal,
+            // so it can bias the starting index: the chain offers no
+            // unpredictable randomness and the owner has to be trusted here.
+            let provenance_hash = self
+                .metadata
+                .get_provenance_hash()
+                .ok_or(PSP34Error::ProvenanceHashNotSet)?;
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(
+                &(
+                    provenance_hash,
+                    offset_seed,
+                    self.env().block_timestamp(),
+                    self.env().block_number(),
+                ),
+                &mut output,
+            );
+            let mut random = [0u8; 8];
+            random.copy_from_slice(&output[..8]);
+            let supply = self.data.max_supply();
+            let starting_index = u64::from_le_bytes(random).checked_rem(supply).unwrap_or(0);
+            let events = self.metadata.reveal(base_uri, starting_index, supply)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn is_revealed(&self) -> bool {
+            self.metadata.is_revealed()
+        }
+
+        #[ink(message)]
+        fn get_starting_index(&self) -> u64 {
+            self.metadata.get_starting_index()
+        }
+    }
+
+    impl PSP34Royalty for Token {
+        #[ink(message)]
+        fn royalty_info(&self, id: Id, sale_price: u128) -> (AccountId, u128) {
+            let id = self.data.normalize_id(id);
+            self.royalty.royalty_info(&id, sale_price)
+        }
+
+        #[ink(message)]
+        fn set_default_royalty(&mut self, receiver: AccountId, basis_points: u16) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.royalty.set_default_royalty(receiver, basis_points)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn delete_default_royalty(&mut self) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.royalty.delete_default_royalty()?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_token_royalty(
+            &mut self,
+            id: Id,
+            receiver: AccountId,
+            basis_points: u16,
+        ) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.royalty.set_token_royalty(id, receiver, basis_points)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn reset_token_royalty(&mut self, id: Id) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.royalty.reset_token_royalty(id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Soulbound for Token {
+        #[ink(message)]
+        fn is_soulbound(&self) -> bool {
+            self.data.is_soulbound()
+        }
+
+        #[ink(message)]
+        fn is_recoverable(&self) -> bool {
+            self.data.is_recoverable()
+        }
+
+        #[ink(message)]
+        fn recover(&mut self, id: Id, to: AccountId) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            let events = self.data.recover(to, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34Rental for Token {
+        #[ink(message)]
+        fn set_user(&mut self, id: Id, user: Option<AccountId>, expires: u64) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            let events = self.data.set_user(
+                self.env().caller(),
+                id,
+                user,
+                expires,
+                self.env().block_timestamp(),
+            )?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn user_of(&self, id: Id) -> Option<AccountId> {
+            let id = self.data.normalize_id(id);
+            self.data.user_of(&id, self.env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn user_expires(&self, id: Id) -> u64 {
+            let id = self.data.normalize_id(id);
+            self.data.user_expires(&id)
+        }
+    }
+
+    impl PSP34TransferLock for Token {
+        #[ink(message)]
+        fn lock_transfer(&mut self, id: Id) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            self.data.lock_transfer(self.env().caller(), &id)
+        }
+
+        #[ink(message)]
+        fn unlock_transfer(&mut self, id: Id) -> Result<(), PSP34Error> {
+            let id = self.data.normalize_id(id);
+            self.data.unlock_transfer(self.env().caller(), &id)
+        }
+
+        #[ink(message)]
+        fn transfer_locker(&self, id: Id) -> Option<AccountId> {
+            let id = self.data.normalize_id(id);
+            self.data.transfer_locker(&id)
+        }
+    }
+
     impl PSP34Traits for Token {
         #[ink(message)]
         fn get_owner(&self) -> AccountId {
@@ -207,8 +737,29 @@ mod token {
             self.data.get_last_token_id()
         }
 
+        #[ink(message)]
+        fn get_approved(&self, id: Id) -> Option<AccountId> {
+            let id = self.data.normalize_id(id);
+            self.data.get_approved(&id)
+        }
+
+        #[ink(message)]
+        fn is_canonical_ids(&self) -> bool {
+            self.data.canonical_ids()
+        }
+
+        #[ink(message)]
+        fn set_canonical_ids(&mut self, enabled: bool) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.data.set_canonical_ids(enabled);
+            Ok(())
+        }
+
         #[ink(message)]
         fn lock(&mut self, token_id: Id) -> Result<(), PSP34Error> {
+            let token_id = self.data.normalize_id(token_id);
             if Some(self.env().caller()) != self.data.owner_of(&token_id) {
                 return Err(PSP34Error::NotTokenOwner);
             }
@@ -217,6 +768,7 @@ mod token {
         
         #[ink(message)]
         fn is_locked_nft(&self, token_id: Id) -> bool {
+            let token_id = self.data.normalize_id(token_id);
             self.data.is_locked_nft(token_id)
         }
         
@@ -224,6 +776,20 @@ mod token {
         fn get_locked_token_count(&self) -> u64 {
             self.data.get_locked_token_count()
         }
+
+        #[ink(message)]
+        fn freeze_all_metadata(&mut self) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            self.data.freeze_all_metadata();
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn is_all_metadata_frozen(&self) -> bool {
+            self.data.is_all_metadata_frozen()
+        }
     
         #[ink(message)]
         fn set_multiple_attributes(
@@ -231,20 +797,65 @@ mod token {
             token_id: Id,
             metadata: Vec<(String, String)>
         ) -> Result<(), PSP34Error> {
+            let token_id = self.data.normalize_id(token_id);
             if self.ownable_data.owner() != Some(self.env().caller()) {
                 return Err(PSP34Error::CallerIsNotOwner)
             }
+            if !self.data.is_mintable_id(&token_id) {
+                return Err(PSP34Error::InvalidInput)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
             if self.is_locked_nft(token_id.clone()) {
                 return Err(PSP34Error::IsLockedToken)
             }
-            self.metadata.set_multiple_attributes(token_id, metadata)
+            let events = self.metadata.set_multiple_attributes(token_id, metadata)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn remove_attributes(&mut self, token_id: Id, keys: Vec<String>) -> Result<(), PSP34Error> {
+            let token_id = self.data.normalize_id(token_id);
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            if self.is_locked_nft(token_id.clone()) {
+                return Err(PSP34Error::IsLockedToken)
+            }
+            let events = self.metadata.remove_attributes(token_id, keys);
+            self.emit_events(events);
+            Ok(())
         }
         
         #[ink(message)]
         fn get_attributes(&self, token_id: Id, attributes: Vec<String>) -> Vec<String> {
+            let token_id = self.data.normalize_id(token_id);
             self.metadata.get_attributes(token_id, attributes)
         }
 
+        #[ink(message)]
+        fn get_attributes_raw(&self, token_id: Id, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>> {
+            let token_id = self.data.normalize_id(token_id);
+            self.metadata.get_attributes_raw(token_id, keys)
+        }
+
+        #[ink(message)]
+        fn get_all_attributes(&self, token_id: Id) -> Vec<(String, String)> {
+            let token_id = self.data.normalize_id(token_id);
+            self.metadata.get_all_attributes(&token_id)
+        }
+
+        #[ink(message)]
+        fn get_all_attributes_raw(&self, token_id: Id) -> Vec<(Vec<u8>, Vec<u8>)> {
+            let token_id = self.data.normalize_id(token_id);
+            self.metadata.get_all_attributes_raw(&token_id)
+        }
+
         #[ink(message)]
         fn get_attribute_count(&self) -> u32 {
             self.metadata.get_attribute_count()
@@ -254,28 +865,142 @@ mod token {
         fn get_attribute_name(&self, index: u32) -> String {
             self.metadata.get_attribute_name(index)
         }
+
+        #[ink(message)]
+        fn get_attribute_name_raw(&self, index: u32) -> Option<Vec<u8>> {
+            self.metadata.get_attribute_name_raw(index)
+        }
         
         #[ink(message)]
-        fn token_uri(&self, token_id: u64) -> String {
+        fn token_uri(&self, token_id: Id) -> String {
+            let token_id = self.data.normalize_id(token_id);
             self.metadata.token_uri(token_id)
         }
 
+        #[ink(message)]
+        fn set_token_uri(&mut self, token_id: Id, uri: Option<String>) -> Result<(), PSP34Error> {
+            let token_id = self.data.normalize_id(token_id);
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            if self.is_locked_nft(token_id.clone()) {
+                return Err(PSP34Error::IsLockedToken)
+            }
+            let events = self.metadata.set_token_uri(token_id, uri)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_uri_suffix(&self) -> String {
+            self.metadata.get_uri_suffix()
+        }
+
+        #[ink(message)]
+        fn is_on_chain_metadata(&self) -> bool {
+            self.metadata.is_on_chain_metadata()
+        }
+
+        #[ink(message)]
+        fn set_on_chain_metadata(&mut self, enabled: bool) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let events = self.metadata.set_on_chain_metadata(enabled)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_uri_suffix(&mut self, suffix: String) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let events = self.metadata.set_uri_suffix(suffix)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
         #[ink(message)]
         fn set_base_uri(&mut self, uri: String) -> Result<(), PSP34Error> {
             if self.ownable_data.owner() != Some(self.env().caller()) {
                 return Err(PSP34Error::CallerIsNotOwner)
             }
-            self.metadata.set_base_uri(uri)
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            let events = self.metadata.set_base_uri(uri)?;
+            self.emit_events(events);
+            Ok(())
         }
     }
 
     impl PSP34Metadata for Token {
         #[ink(message)]
         fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+            let id = self.data.normalize_id(id);
+            if id == self.data.collection_id(self.env().account_id()) {
+                return self.metadata.get_collection_attribute(&key)
+            }
             self.metadata.get_attribute(id, key)
         }
     }
 
+    impl PSP34CollectionMetadata for Token {
+        #[ink(message)]
+        fn get_collection_metadata(&self) -> metadata::CollectionMetadata {
+            self.metadata.get_collection_metadata()
+        }
+
+        #[ink(message)]
+        fn set_collection_metadata(
+            &mut self,
+            collection: metadata::CollectionMetadata,
+        ) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if self.data.is_all_metadata_frozen() {
+                return Err(PSP34Error::MetadataFrozen)
+            }
+            self.metadata.set_collection_metadata(collection);
+            Ok(())
+        }
+    }
+
+    impl PSP34Listing for Token {
+        #[ink(message)]
+        fn tokens_of_owner(&self, owner: AccountId, offset: u128, limit: u32) -> Vec<Id> {
+            self.data.tokens_of_owner(owner, offset, limit)
+        }
+
+        #[ink(message)]
+        fn all_tokens(&self, offset: u128, limit: u32) -> Vec<Id> {
+            self.data.all_tokens(offset, limit)
+        }
+
+        #[ink(message)]
+        fn minted_count(&self) -> u128 {
+            self.data.minted_count()
+        }
+
+        #[ink(message)]
+        fn owners_of(&self, ids: Vec<Id>) -> Vec<Option<AccountId>> {
+            ids.into_iter()
+                .map(|id| self.data.owner_of(&self.data.normalize_id(id)))
+                .collect()
+        }
+    }
+
     impl Ownable for Token {
         #[ink(message)]
         fn owner(&self) -> Option<AccountId> {
@@ -299,8 +1024,103 @@ mod token {
         }
     }
     
+    impl AdminTrait for Token {
+        #[ink(message)]
+        fn withdraw_fee(&mut self, value: u128, receiver: AccountId) -> Result<(), PSP34Error> {
+            if self.ownable_data.owner() != Some(self.env().caller()) {
+                return Err(PSP34Error::CallerIsNotOwner)
+            }
+            if value > self.env().balance() {
+                return Err(PSP34Error::NotEnoughBalance);
+            }
+            if self.env().transfer(receiver, value).is_err() {
+                return Err(PSP34Error::WithdrawFeeError);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_balance(&mut self) -> Result<u128, PSP34Error> {
+            Ok(self.env().balance())
+        }
+    }
+
     #[cfg(test)]
     mod tests {
-        crate::tests!(Token, Token::new);
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        type Event = <Token as ink::reflect::ContractEventBase>::Type;
+
+        // Creates a collection of 10 tokens owned by Alice.
+        fn setup() -> Token {
+            Token::new(
+                default_accounts::<E>().alice,
+                10,
+                Some(String::from("Collection")),
+                Some(String::from("COL")),
+            )
+        }
+
+        fn decode_events(shift: usize) -> Vec<Event> {
+            recorded_events()
+                .skip(shift)
+                .map(|e| <Event as scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                .collect()
+        }
+
+        // The PSP34 suite is expanded in a child module of this one.
+        crate::tests!(Token, (super::setup));
+
+        #[ink::test]
+        fn soulbound_works() {
+            let accounts = default_accounts::<E>();
+            // Create a soulbound collection which can not be recovered.
+            let mut token = Token::new_soulbound(accounts.alice, 10, None, None, false);
+            assert!(token.is_soulbound());
+            assert!(!token.is_recoverable());
+            assert_eq!(token.mint_to(accounts.bob), Ok(()));
+            assert_eq!(token.mint_to(accounts.bob), Ok(()));
+            // The holder can neither transfer nor approve the token.
+            set_caller::<E>(accounts.bob);
+            assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![]), Err(PSP34Error::NonTransferable));
+            assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), true), Err(PSP34Error::NonTransferable));
+            assert_eq!(token.approve(accounts.charlie, None, true), Err(PSP34Error::NonTransferable));
+            // Nor lend it.
+            assert_eq!(
+                token.set_user(Id::U64(1), Some(accounts.charlie), u64::MAX),
+                Err(PSP34Error::NonTransferable)
+            );
+            assert_eq!(token.user_of(Id::U64(1)), None);
+            // The holder can burn its own token.
+            assert_eq!(token.burn(accounts.bob, Id::U64(1)), Ok(()));
+            // The issuer can revoke a token, but not move it.
+            set_caller::<E>(accounts.alice);
+            assert_eq!(token.recover(Id::U64(2), accounts.charlie), Err(PSP34Error::NonTransferable));
+            assert_eq!(token.burn(accounts.bob, Id::U64(2)), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn soulbound_recovery_works() {
+            let accounts = default_accounts::<E>();
+            // Create a recoverable soulbound collection.
+            let mut token = Token::new_soulbound(accounts.alice, 10, None, None, true);
+            assert!(token.is_recoverable());
+            assert_eq!(token.mint_to(accounts.bob), Ok(()));
+            // Only the issuer can recover a token.
+            set_caller::<E>(accounts.bob);
+            assert_eq!(token.recover(Id::U64(1), accounts.charlie), Err(PSP34Error::CallerIsNotOwner));
+            set_caller::<E>(accounts.alice);
+            let start = recorded_events().count();
+            assert_eq!(token.recover(Id::U64(1), accounts.charlie), Ok(()));
+            let events = decode_events(start);
+            assert!(matches!(
+                &events[0],
+                Event::Transfer(Transfer { from, to, id })
+                    if *from == Some(accounts.bob) && *to == Some(accounts.charlie) && *id == Id::U64(1)
+            ));
+            assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.charlie));
+        }
     }
 }
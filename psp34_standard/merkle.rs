@@ -0,0 +1,34 @@
+use ink::{
+    env::hash::{HashOutput, Keccak256},
+    primitives::AccountId,
+};
+
+pub type Hash = [u8; 32];
+
+/// Leaf of an allowlist tree: keccak256 of the SCALE encoded
+/// `(account, max_quantity)` pair.
+pub fn leaf(account: AccountId, max_quantity: u32) -> Hash {
+    let mut output = <Keccak256 as HashOutput>::Type::default();
+    ink::env::hash_encoded::<Keccak256, _>(&(account, max_quantity), &mut output);
+    output
+}
+
+/// Parent of two nodes. The pair is sorted before hashing, so proofs do not
+/// need to carry the side of each sibling.
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(first);
+    input[32..].copy_from_slice(second);
+    let mut output = <Keccak256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Keccak256>(&input, &mut output);
+    output
+}
+
+/// Checks that `leaf` belongs to the tree with the given `root`.
+pub fn verify(root: &Hash, leaf: Hash, proof: &[Hash]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
@@ -2,15 +2,58 @@ use crate::{
     data::{Id, PSP34Event},
     PSP34Error,
 };
-use ink::{prelude::{string::String, vec::Vec, vec}, storage::Mapping};
+use ink::{prelude::{string::{String, ToString}, vec::Vec, vec}, storage::Mapping};
+
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+/// Suffix appended to `{base}{id}` token URIs unless configured otherwise.
+const DEFAULT_URI_SUFFIX: &str = ".json";
+/// Placeholder replaced by the token id when present in the base URI.
+const ID_PLACEHOLDER: &str = "{id}";
+/// Prefix of the token URIs served in on-chain metadata mode.
+const JSON_DATA_URI_PREFIX: &str = "data:application/json;base64,";
+/// Attributes which become top level fields of the on-chain JSON document.
+const JSON_FIELDS: [&str; 3] = ["name", "description", "image"];
+
+/// Metadata describing the whole collection. Every field is also readable
+/// with `get_attribute(collection_id(), key)` under the key named after it,
+/// `external_url` being `b"external_url"`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct CollectionMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    pub external_url: Option<String>,
+    pub image: Option<String>,
+}
+
+/// Attribute key of a token by position, in the order keys were first set.
+type TokenAttributeKeys = Mapping<(Id, u32), Vec<u8>>;
+/// Position of an attribute key of a token in `TokenAttributeKeys`.
+type TokenAttributeIndexes = Mapping<(Id, Vec<u8>), u32>;
 
 #[ink::storage_item]
 #[derive(Default, Debug)]
 pub struct Data {
+    collection: CollectionMetadata,
+    base_uri: Option<String>,
     attributes: Mapping<(Id, Vec<u8>), Vec<u8>>,
+    token_attribute_keys: TokenAttributeKeys,
+    token_attribute_indexes: TokenAttributeIndexes,
+    token_attribute_count: Mapping<Id, u32>,
     attribute_count: u32,
     attribute_names: Mapping<u32, Vec<u8>>,
     is_attribute: Mapping<String, bool>,
+    token_uris: Mapping<Id, String>,
+    uri_suffix: Option<String>,
+    on_chain_metadata: bool,
+    pre_reveal_uri: Option<String>,
+    provenance_hash: Option<[u8; 32]>,
+    revealed: bool,
+    starting_index: u64,
+    reveal_supply: u64,
 }
 
 impl Data {
@@ -24,23 +67,212 @@ impl Data {
         key: Vec<u8>,
         value: Vec<u8>,
     ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        let event = self._set_attribute(id.clone(), key, value)?;
+        Ok(vec![event, PSP34Event::MetadataUpdate { id }])
+    }
+
+    fn _set_attribute(
+        &mut self,
+        id: Id,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<PSP34Event, PSP34Error> {
+        if !self.token_attribute_indexes.contains((&id, &key)) {
+            let index = self.token_attribute_count.get(&id).unwrap_or(0);
+            let count = index
+                .checked_add(1)
+                .ok_or(PSP34Error::Custom(String::from("Fail to increase attribute count")))?;
+            self.token_attribute_keys.insert((&id, index), &key);
+            self.token_attribute_indexes.insert((&id, &key), &index);
+            self.token_attribute_count.insert(&id, &count);
+        }
         self.attributes.insert((&id, &key), &value);
-        Ok(vec![PSP34Event::AttributeSet {
+        Ok(PSP34Event::AttributeSet {
             id,
             key,
             data: value,
-        }])
+        })
+    }
+
+    /// Removes attribute `key` of `id`. Nothing happens if it is not set.
+    pub fn remove_attribute(&mut self, id: Id, key: Vec<u8>) -> Vec<PSP34Event> {
+        match self._remove_attribute(id.clone(), key) {
+            Some(event) => vec![event, PSP34Event::MetadataUpdate { id }],
+            None => vec![],
+        }
+    }
+
+    fn _remove_attribute(&mut self, id: Id, key: Vec<u8>) -> Option<PSP34Event> {
+        let index = self.token_attribute_indexes.take((&id, &key))?;
+        let last_index = self.token_attribute_count.get(&id).unwrap_or(1) - 1;
+        if index != last_index {
+            if let Some(last_key) = self.token_attribute_keys.get((&id, last_index)) {
+                self.token_attribute_keys.insert((&id, index), &last_key);
+                self.token_attribute_indexes.insert((&id, &last_key), &index);
+            }
+        }
+        self.token_attribute_keys.remove((&id, last_index));
+        if last_index == 0 {
+            self.token_attribute_count.remove(&id);
+        } else {
+            self.token_attribute_count.insert(&id, &last_index);
+        }
+        self.attributes.remove((&id, &key));
+        Some(PSP34Event::AttributeRemoved { id, key })
+    }
+
+    /// Removes every attribute stored for `id`.
+    pub fn clear_attributes(&mut self, id: &Id) {
+        let count = self.token_attribute_count.take(id).unwrap_or(0);
+        for index in 0..count {
+            if let Some(key) = self.token_attribute_keys.take((id, index)) {
+                self.token_attribute_indexes.remove((id, &key));
+                self.attributes.remove((id, &key));
+            }
+        }
+    }
+
+    /// Returns every attribute set for `id` as raw bytes.
+    pub fn get_all_attributes_raw(&self, id: &Id) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let count = self.token_attribute_count.get(id).unwrap_or(0);
+        (0..count)
+            .filter_map(|index| self.token_attribute_keys.get((id, index)))
+            .filter_map(|key| self.attributes.get((id, &key)).map(|value| (key, value)))
+            .collect()
+    }
+
+    /// Returns every attribute set for `id`. Invalid UTF-8 is replaced, the
+    /// raw bytes are available from `get_all_attributes_raw`.
+    pub fn get_all_attributes(&self, id: &Id) -> Vec<(String, String)> {
+        self.get_all_attributes_raw(id)
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    String::from_utf8_lossy(&key).into_owned(),
+                    String::from_utf8_lossy(&value).into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn get_collection_metadata(&self) -> CollectionMetadata {
+        self.collection.clone()
+    }
+
+    pub fn set_collection_metadata(&mut self, collection: CollectionMetadata) {
+        self.collection = collection;
+    }
+
+    /// Returns the collection level attribute `key`: a field of the
+    /// collection metadata or `baseURI`.
+    pub fn get_collection_attribute(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = match key {
+            b"name" => &self.collection.name,
+            b"symbol" => &self.collection.symbol,
+            b"description" => &self.collection.description,
+            b"external_url" => &self.collection.external_url,
+            b"image" => &self.collection.image,
+            b"baseURI" => &self.base_uri,
+            _ => return None,
+        };
+        value.clone().map(String::into_bytes)
     }
 
     pub fn get_attribute_count(&self) -> u32 {
         self.attribute_count
     }
 
-    pub fn set_base_uri(&mut self, uri: String) -> Result<(), PSP34Error> {
-        self.set_attribute(Id::U8(0), String::from("baseURI").into_bytes(), uri.into_bytes());
+    pub fn set_base_uri(&mut self, uri: String) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        self.base_uri = Some(uri);
+        Ok(all_tokens_updated())
+    }
+
+    pub fn get_pre_reveal_uri(&self) -> Option<String> {
+        self.pre_reveal_uri.clone()
+    }
+
+    /// Sets the placeholder URI returned for every token until the
+    /// collection is revealed.
+    pub fn set_pre_reveal_uri(&mut self, uri: Option<String>) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        self.pre_reveal_uri = uri;
+        Ok(all_tokens_updated())
+    }
+
+    pub fn get_provenance_hash(&self) -> Option<[u8; 32]> {
+        self.provenance_hash
+    }
+
+    /// Commits to the hash of the final metadata. It can be set only once,
+    /// before `minting_started`.
+    pub fn set_provenance_hash(
+        &mut self,
+        provenance_hash: [u8; 32],
+        minting_started: bool,
+    ) -> Result<(), PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        if self.provenance_hash.is_some() {
+            return Err(PSP34Error::ProvenanceHashAlreadySet);
+        }
+        if minting_started {
+            return Err(PSP34Error::MintingStarted);
+        }
+        self.provenance_hash = Some(provenance_hash);
         Ok(())
     }
 
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    pub fn get_starting_index(&self) -> u64 {
+        self.starting_index
+    }
+
+    /// Sets the final base URI and the offset `Id::U64` tokens are shifted by
+    /// within `1..=supply` when building their URIs. Nothing about the reveal
+    /// can change afterwards.
+    pub fn reveal(
+        &mut self,
+        base_uri: String,
+        starting_index: u64,
+        supply: u64,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        if self.provenance_hash.is_none() {
+            return Err(PSP34Error::ProvenanceHashNotSet);
+        }
+        let events = self.set_base_uri(base_uri)?;
+        self.revealed = true;
+        self.starting_index = starting_index;
+        self.reveal_supply = supply;
+        Ok(events)
+    }
+
+    /// Maps a token to the metadata it got assigned by the reveal.
+    fn revealed_id(&self, token_id: Id) -> Id {
+        match token_id {
+            Id::U64(id) if self.revealed && (1..=self.reveal_supply).contains(&id) => {
+                let shift = self.starting_index % self.reveal_supply;
+                Id::U64((id - 1 + shift) % self.reveal_supply + 1)
+            }
+            token_id => token_id,
+        }
+    }
+
+    pub fn get_attribute_name_raw(&self, index: u32) -> Option<Vec<u8>> {
+        self.attribute_names.get(index)
+    }
+
     pub fn get_attribute_name(&self, index: u32) -> String {
         let attribute = self.attribute_names.get(&index);
 
@@ -55,33 +287,153 @@ impl Data {
         }
     }
 
-    pub fn token_uri(&self, token_id: u64) -> String {
-        let value = self.get_attribute(Id::U8(0), String::from("baseURI").into_bytes());
-        let mut token_uri = String::from("");
+    /// Returns the placeholder URI before the reveal, then the URI set for the
+    /// token, the JSON data URI in on-chain metadata mode, or the base URI with
+    /// `{id}` replaced by the token id, `{base}{id}{suffix}` without placeholder.
+    pub fn token_uri(&self, token_id: Id) -> String {
+        if !self.revealed {
+            if let Some(uri) = &self.pre_reveal_uri {
+                return uri.clone();
+            }
+        }
+        if let Some(uri) = self.token_uris.get(&token_id) {
+            return uri;
+        }
+        if self.on_chain_metadata {
+            return String::from(JSON_DATA_URI_PREFIX)
+                + &base64_encode(self.token_json(&token_id).as_bytes());
+        }
+        let base_uri = self.base_uri.clone().unwrap_or_default();
+        let id = id_to_string(&self.revealed_id(token_id));
 
-        if let Some(value_in_bytes) = value {
-            if let Ok(value_in_string) = String::from_utf8(value_in_bytes) {
-                token_uri = value_in_string;
-            }                 
+        if base_uri.contains(ID_PLACEHOLDER) {
+            return base_uri.replace(ID_PLACEHOLDER, &id);
+        }
+        base_uri + &id + self.get_uri_suffix().as_str()
+    }
+
+    pub fn is_on_chain_metadata(&self) -> bool {
+        self.on_chain_metadata
+    }
+
+    /// Makes `token_uri` serve the token attributes as a JSON document
+    /// embedded in a data URI instead of pointing at the base URI.
+    pub fn set_on_chain_metadata(&mut self, enabled: bool) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        self.on_chain_metadata = enabled;
+        Ok(all_tokens_updated())
+    }
+
+    /// Builds the JSON metadata document of `token_id` out of its attributes.
+    /// `name`, `description` and `image` become top level fields, every other
+    /// attribute set for the token goes to the `attributes` array.
+    fn token_json(&self, token_id: &Id) -> String {
+        let attribute = |key: &[u8]| {
+            self.attributes
+                .get((token_id, &key.to_vec()))
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        };
+        let mut json = String::from("{");
+        for field in JSON_FIELDS {
+            json.push('"');
+            json.push_str(field);
+            json.push_str("\":\"");
+            json.push_str(&json_escape(&attribute(field.as_bytes()).unwrap_or_default()));
+            json.push_str("\",");
+        }
+        json.push_str("\"attributes\":[");
+        let mut first = true;
+        for index in 1..=self.attribute_count {
+            let Some(key) = self.attribute_names.get(index) else {
+                continue;
+            };
+            if JSON_FIELDS.iter().any(|field| field.as_bytes() == key.as_slice()) {
+                continue;
+            }
+            let Some(value) = attribute(&key) else {
+                continue;
+            };
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            json.push_str("{\"trait_type\":\"");
+            json.push_str(&json_escape(&String::from_utf8_lossy(&key)));
+            json.push_str("\",\"value\":\"");
+            json.push_str(&json_escape(&value));
+            json.push_str("\"}");
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Sets a URI for `token_id` overriding the base URI, or removes it.
+    pub fn set_token_uri(
+        &mut self,
+        token_id: Id,
+        uri: Option<String>,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        if let Some(uri) = uri {
+            self.token_uris.insert(&token_id, &uri);
+        } else {
+            self.token_uris.remove(&token_id);
         }
+        Ok(vec![PSP34Event::MetadataUpdate { id: token_id }])
+    }
+
+    pub fn get_uri_suffix(&self) -> String {
+        self.uri_suffix
+            .clone()
+            .unwrap_or_else(|| String::from(DEFAULT_URI_SUFFIX))
+    }
 
-        token_uri = token_uri + &String::from("1") + &String::from(".json");
-        token_uri
+    pub fn set_uri_suffix(&mut self, suffix: String) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.revealed {
+            return Err(PSP34Error::AlreadyRevealed);
+        }
+        self.uri_suffix = Some(suffix);
+        Ok(all_tokens_updated())
     }
 
     pub fn set_multiple_attributes(
         &mut self,
         token_id: Id,
         metadata: Vec<(String, String)>
-    ) -> Result<(), PSP34Error> {
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
         if token_id == Id::U64(0){
             return Err(PSP34Error::InvalidInput)
         }
-        for (attribute, value) in &metadata {
-            self.add_attribute_name(&attribute.clone().into_bytes());
-            self.set_attribute(token_id.clone(), attribute.clone().into_bytes(), value.clone().into_bytes());
+        let mut events = Vec::new();
+        for (attribute, value) in metadata {
+            self.add_attribute_name(&attribute.clone().into_bytes())?;
+            events.push(self._set_attribute(token_id.clone(), attribute.into_bytes(), value.into_bytes())?);
         }
-        Ok(())
+        if !events.is_empty() {
+            events.push(PSP34Event::MetadataUpdate { id: token_id });
+        }
+        Ok(events)
+    }
+
+    pub fn remove_attributes(&mut self, token_id: Id, keys: Vec<String>) -> Vec<PSP34Event> {
+        let mut events: Vec<PSP34Event> = keys
+            .into_iter()
+            .filter_map(|key| self._remove_attribute(token_id.clone(), key.into_bytes()))
+            .collect();
+        if !events.is_empty() {
+            events.push(PSP34Event::MetadataUpdate { id: token_id });
+        }
+        events
+    }
+
+    pub fn get_attributes_raw(&self, token_id: Id, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>> {
+        keys.into_iter()
+            .map(|key| self.get_attribute(token_id.clone(), key))
+            .collect()
     }
 
     pub fn get_attributes(&self, token_id: Id, attributes: Vec<String>) -> Vec<String> {
@@ -104,6 +456,8 @@ impl Data {
         ret
     }
 
+    /// Registers `attribute_input` as an attribute name of the collection,
+    /// unless it is already known.
     fn add_attribute_name(&mut self, attribute_input: &Vec<u8>) -> Result<(), PSP34Error> {
         if let Ok(attr_input) = String::from_utf8((*attribute_input).clone()) {
             let exist: bool = self.is_attribute.get(&attr_input).is_some();
@@ -118,10 +472,79 @@ impl Data {
                     return Err(PSP34Error::Custom(String::from("Fail to increase attribute count"))); 
                 }
             } else {
-                return Err(PSP34Error::Custom(String::from("Attribute input exists"))); 
+                return Ok(());
             } 
         } else {
             return Err(PSP34Error::Custom(String::from("Attribute input error")));
         }
     }
 }
+
+/// Tells indexers to refresh the metadata of every token, covering the whole
+/// `Id::U64` range like ERC-4906 does with the whole `uint256` range.
+fn all_tokens_updated() -> Vec<PSP34Event> {
+    vec![PSP34Event::BatchMetadataUpdate {
+        from_id: Id::U64(0),
+        to_id: Id::U64(u64::MAX),
+    }]
+}
+
+/// Formats an id for use in a token URI: decimal for the integer variants,
+/// lowercase hex for `Id::Bytes`.
+fn id_to_string(id: &Id) -> String {
+    match id {
+        Id::U8(val) => val.to_string(),
+        Id::U16(val) => val.to_string(),
+        Id::U32(val) => val.to_string(),
+        Id::U64(val) => val.to_string(),
+        Id::U128(val) => val.to_string(),
+        Id::Bytes(val) => {
+            const HEX: &[u8; 16] = b"0123456789abcdef";
+            val.iter()
+                .flat_map(|byte| [HEX[(byte >> 4) as usize] as char, HEX[(byte & 0x0f) as usize] as char])
+                .collect()
+        }
+    }
+}
+
+/// Escapes `value` for use inside a JSON string literal.
+fn json_escape(value: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0c}' => escaped.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                escaped.push_str("\\u00");
+                escaped.push(HEX[(c as usize) >> 4] as char);
+                escaped.push(HEX[(c as usize) & 0x0f] as char);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Standard base64 encoding with padding.
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for position in 0..4 {
+            if position <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * position) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
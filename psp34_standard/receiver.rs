@@ -0,0 +1,132 @@
+use crate::{Id, PSP34Error, PSP34ReceiverError};
+use ink::{
+    prelude::{format, vec::Vec},
+    primitives::AccountId,
+};
+
+/// Calls `PSP34Receiver::before_received` on the contract `to`.
+pub(crate) fn before_received(
+    operator: AccountId,
+    from: AccountId,
+    to: AccountId,
+    id: Id,
+    data: Vec<u8>,
+) -> Result<(), PSP34Error> {
+    check_response(invoke(operator, from, to, id, data))
+}
+
+type Response = ink::env::Result<ink::MessageResult<Result<(), PSP34ReceiverError>>>;
+
+#[cfg(not(test))]
+fn invoke(operator: AccountId, from: AccountId, to: AccountId, id: Id, data: Vec<u8>) -> Response {
+    use ink::env::{
+        call::{build_call, ExecutionInput, Selector},
+        DefaultEnvironment,
+    };
+
+    build_call::<DefaultEnvironment>()
+        .call(to)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                "PSP34Receiver::before_received"
+            )))
+            .push_arg(operator)
+            .push_arg(from)
+            .push_arg(id)
+            .push_arg(data),
+        )
+        .returns::<Result<(), PSP34ReceiverError>>()
+        .try_invoke()
+}
+
+/// Cross-contract calls are not supported off-chain, so tests register the
+/// response of every receiving contract instead.
+#[cfg(test)]
+fn invoke(operator: AccountId, from: AccountId, to: AccountId, id: Id, data: Vec<u8>) -> Response {
+    mock::invoke(operator, from, to, id, data)
+}
+
+/// Decides whether a transfer goes through based on the response of the
+/// receiving contract. Contracts which do not implement `PSP34Receiver`
+/// accept every token, as their missing message either can not decode the
+/// input or is not callable. An explicit rejection, a trap or any other
+/// failure reverts the transfer.
+pub fn check_response(result: Response) -> Result<(), PSP34Error> {
+    match result {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(error))) => Err(error.into()),
+        Ok(Err(ink::LangError::CouldNotReadInput)) => Ok(()),
+        Err(ink::env::Error::NotCallable) => Ok(()),
+        Ok(Err(error)) => Err(PSP34Error::SafeTransferCheckFailed(format!("{:?}", error))),
+        Err(error) => Err(PSP34Error::SafeTransferCheckFailed(format!("{:?}", error))),
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+    use ink::env::{
+        test::{set_callee, set_caller},
+        DefaultEnvironment,
+    };
+    use psp34_receiver_mock::receiver::{PSP34Receiver, Receiver};
+    use scale::{Decode, Encode};
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    pub type Received = (AccountId, AccountId, Id, Vec<u8>);
+
+    std::thread_local! {
+        static RECEIVERS: RefCell<BTreeMap<AccountId, Receiver>> = const { RefCell::new(BTreeMap::new()) };
+        static RESPONSES: RefCell<BTreeMap<AccountId, fn() -> Response>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    /// Makes the receiver contract of this repository reachable at `at`.
+    pub fn deploy(at: AccountId, receiver: Receiver) {
+        RECEIVERS.with(|receivers| receivers.borrow_mut().insert(at, receiver));
+    }
+
+    /// Makes the contract `to` answer every `before_received` call with
+    /// `response`, for failures a receiver contract can not return.
+    pub fn set_response(to: AccountId, response: fn() -> Response) {
+        RESPONSES.with(|responses| responses.borrow_mut().insert(to, response));
+    }
+
+    /// Returns the last token accepted by the receiver deployed at `at`.
+    pub fn last_received(at: AccountId) -> Option<Received> {
+        RECEIVERS.with(|receivers| {
+            let received = receivers.borrow()[&at].last_received();
+            convert(&received)
+        })
+    }
+
+    /// Values cross the contract boundary SCALE encoded, as they would on chain.
+    fn convert<T: Decode>(value: &impl Encode) -> T {
+        T::decode(&mut &value.encode()[..]).expect("incompatible encoding")
+    }
+
+    pub(super) fn invoke(
+        operator: AccountId,
+        from: AccountId,
+        to: AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> Response {
+        if let Some(response) = RESPONSES.with(|responses| responses.borrow().get(&to).copied()) {
+            return response();
+        }
+        RECEIVERS.with(|receivers| {
+            let mut receivers = receivers.borrow_mut();
+            let receiver = receivers
+                .get_mut(&to)
+                .expect("no receiver deployed at the address");
+            let caller = ink::env::caller::<DefaultEnvironment>();
+            let callee = ink::env::account_id::<DefaultEnvironment>();
+            set_caller::<DefaultEnvironment>(callee);
+            set_callee::<DefaultEnvironment>(to);
+            let result = receiver.before_received(operator, from, convert(&id), data);
+            set_callee::<DefaultEnvironment>(callee);
+            set_caller::<DefaultEnvironment>(caller);
+            Ok(Ok(convert(&result)))
+        })
+    }
+}
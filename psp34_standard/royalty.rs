@@ -0,0 +1,92 @@
+use crate::{
+    data::{Id, PSP34Event},
+    PSP34Error,
+};
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// Royalties are expressed in basis points of the sale price.
+pub const BASIS_POINTS_DENOMINATOR: u16 = 10_000;
+
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct RoyaltyData {
+    default_royalty: Option<(AccountId, u16)>,
+    token_royalties: Mapping<Id, (AccountId, u16)>,
+}
+
+impl RoyaltyData {
+    pub fn new() -> RoyaltyData {
+        Default::default()
+    }
+
+    /// Returns the royalty receiver of token `id` and the royalty due for
+    /// `sale_price`. The token royalty takes precedence over the collection
+    /// one. Without any royalty the zero account and no amount are returned.
+    pub fn royalty_info(&self, id: &Id, sale_price: u128) -> (AccountId, u128) {
+        let Some((receiver, basis_points)) = self.token_royalties.get(id).or(self.default_royalty) else {
+            return (AccountId::from([0u8; 32]), 0);
+        };
+        let denominator = BASIS_POINTS_DENOMINATOR as u128;
+        let basis_points = basis_points as u128;
+        // Split the price so the multiplication can not overflow.
+        let amount = sale_price / denominator * basis_points
+            + sale_price % denominator * basis_points / denominator;
+        (receiver, amount)
+    }
+
+    pub fn set_default_royalty(
+        &mut self,
+        receiver: AccountId,
+        basis_points: u16,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if basis_points > BASIS_POINTS_DENOMINATOR {
+            return Err(PSP34Error::InvalidInput);
+        }
+        self.default_royalty = Some((receiver, basis_points));
+        Ok(vec![PSP34Event::RoyaltySet {
+            id: None,
+            receiver: Some(receiver),
+            basis_points,
+        }])
+    }
+
+    pub fn delete_default_royalty(&mut self) -> Result<Vec<PSP34Event>, PSP34Error> {
+        self.default_royalty = None;
+        Ok(vec![PSP34Event::RoyaltySet {
+            id: None,
+            receiver: None,
+            basis_points: 0,
+        }])
+    }
+
+    pub fn set_token_royalty(
+        &mut self,
+        id: Id,
+        receiver: AccountId,
+        basis_points: u16,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if basis_points > BASIS_POINTS_DENOMINATOR {
+            return Err(PSP34Error::InvalidInput);
+        }
+        self.token_royalties.insert(&id, &(receiver, basis_points));
+        Ok(vec![PSP34Event::RoyaltySet {
+            id: Some(id),
+            receiver: Some(receiver),
+            basis_points,
+        }])
+    }
+
+    /// Removes the royalty of token `id`, so the collection royalty applies.
+    pub fn reset_token_royalty(&mut self, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+        self.token_royalties.remove(&id);
+        Ok(vec![PSP34Event::RoyaltySet {
+            id: Some(id),
+            receiver: None,
+            basis_points: 0,
+        }])
+    }
+}
@@ -4,7 +4,10 @@ use ink::{
 };
 
 use crate::data::Id;
-use crate::errors::PSP34Error;
+use crate::errors::{PSP34Error, PSP34ReceiverError};
+use crate::launchpad::MintPhase;
+use crate::metadata::CollectionMetadata;
+use crate::merkle::Hash;
 use ink::{prelude::{string::String}};
 
 #[ink::trait_definition]
@@ -37,6 +40,31 @@ pub trait PSP34 {
     fn owner_of(&self, id: Id) -> Option<AccountId>;
 }
 
+/// Implemented by contracts which want to react to the PSP34 tokens they
+/// receive. The collection calls it after moving the token, so returning an
+/// error reverts the whole transfer.
+#[ink::trait_definition]
+pub trait PSP34Receiver {
+    /// Called when token `id` is transferred from `from` to the contract by
+    /// `operator`, with the `data` passed to the transfer.
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> Result<(), PSP34ReceiverError>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Burnable {
+    /// Destroys token `id` owned by `account`.
+    /// The caller has to be the owner of the token or an approved operator.
+    #[ink(message)]
+    fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error>;
+}
+
 #[ink::trait_definition]
 pub trait PSP34Traits {
     #[ink(message)]
@@ -49,24 +77,75 @@ pub trait PSP34Traits {
         metadata: Vec<(String, String)>
     ) -> Result<(), PSP34Error>;
     
+    /// Removes the given attributes of a token. Keys which are not set are
+    /// skipped.
+    #[ink(message)]
+    fn remove_attributes(&mut self, token_id: Id, keys: Vec<String>) -> Result<(), PSP34Error>;
+
+    /// Values which are not valid UTF-8 are returned as empty strings, use
+    /// `get_attributes_raw` to read them.
     #[ink(message)]
     fn get_attributes(&self, token_id: Id, attributes: Vec<String>) -> Vec<String>;
+
+    #[ink(message)]
+    fn get_attributes_raw(&self, token_id: Id, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>>;
+
+    /// Returns every attribute set for a token. Invalid UTF-8 is replaced.
+    #[ink(message)]
+    fn get_all_attributes(&self, token_id: Id) -> Vec<(String, String)>;
+
+    #[ink(message)]
+    fn get_all_attributes_raw(&self, token_id: Id) -> Vec<(Vec<u8>, Vec<u8>)>;
     
     #[ink(message)]
     fn get_attribute_count(&self) -> u32;
     
     #[ink(message)]
     fn get_attribute_name(&self, index: u32) -> String;
+
+    #[ink(message)]
+    fn get_attribute_name_raw(&self, index: u32) -> Option<Vec<u8>>;
     
     #[ink(message)]
-    fn token_uri(&self, token_id: u64) -> String;
+    fn token_uri(&self, token_id: Id) -> String;
+
+    /// Sets a URI for a single token overriding the base URI, `None` removes it.
+    #[ink(message)]
+    fn set_token_uri(&mut self, token_id: Id, uri: Option<String>) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_uri_suffix(&self) -> String;
+
+    #[ink(message)]
+    fn is_on_chain_metadata(&self) -> bool;
+
+    /// Makes `token_uri` return a `data:application/json;base64,` document
+    /// built from the token attributes.
+    #[ink(message)]
+    fn set_on_chain_metadata(&mut self, enabled: bool) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn set_uri_suffix(&mut self, suffix: String) -> Result<(), PSP34Error>;
     
     #[ink(message)]
     fn get_owner(&self) -> AccountId ;
     
     #[ink(message)]
     fn get_last_token_id(&self) -> u64;
+
+    /// Returns the single account approved for token `id`, if any.
+    #[ink(message)]
+    fn get_approved(&self, id: Id) -> Option<AccountId>;
+
+    /// Whether ids of any integer variant are accepted for the `Id::U64`
+    /// tokens of the collection, e.g. `Id::U8(1)` for `Id::U64(1)`.
+    #[ink(message)]
+    fn is_canonical_ids(&self) -> bool;
+
+    #[ink(message)]
+    fn set_canonical_ids(&mut self, enabled: bool) -> Result<(), PSP34Error>;
     
+    /// Permanently freezes the metadata of a single token.
     #[ink(message)]
     fn lock(&mut self, token_id: Id) -> Result<(), PSP34Error>;
     
@@ -75,14 +154,247 @@ pub trait PSP34Traits {
     
     #[ink(message)]
     fn get_locked_token_count(&self) -> u64;
+
+    /// Permanently freezes the metadata of the whole collection.
+    #[ink(message)]
+    fn freeze_all_metadata(&mut self) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn is_all_metadata_frozen(&self) -> bool;
+}
+
+#[ink::trait_definition]
+pub trait PSP34TransferLock {
+    /// Blocks transfers and burns of token `id` until the caller unlocks it.
+    /// The caller has to be the owner of the token or an operator approved for
+    /// all its tokens.
+    #[ink(message)]
+    fn lock_transfer(&mut self, id: Id) -> Result<(), PSP34Error>;
+
+    /// Lifts the transfer lock of token `id`. Only the locker can call it.
+    #[ink(message)]
+    fn unlock_transfer(&mut self, id: Id) -> Result<(), PSP34Error>;
+
+    /// Returns the account which locked transfers of token `id`, if any.
+    #[ink(message)]
+    fn transfer_locker(&self, id: Id) -> Option<AccountId>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Soulbound {
+    /// Whether tokens of the collection can never be transferred nor approved.
+    #[ink(message)]
+    fn is_soulbound(&self) -> bool;
+
+    /// Whether the issuer can move tokens of the soulbound collection.
+    #[ink(message)]
+    fn is_recoverable(&self) -> bool;
+
+    /// Moves token `id` to `to`, e.g. a new account of a holder who lost
+    /// access to theirs. Only the owner of a recoverable collection can call it.
+    #[ink(message)]
+    fn recover(&mut self, id: Id, to: AccountId) -> Result<(), PSP34Error>;
+}
+
+/// Usage rights separate from ownership, in the style of ERC-4907.
+#[ink::trait_definition]
+pub trait PSP34Rental {
+    /// Lets `user` use token `id` until timestamp `expires` (in milliseconds),
+    /// or removes the current user when `user` is `None`. The caller has to be
+    /// the owner of the token or an approved operator. The user is removed
+    /// when the token is transferred. Fails with `NonTransferable` for a soulbound
+    /// collection.
+    #[ink(message)]
+    fn set_user(&mut self, id: Id, user: Option<AccountId>, expires: u64) -> Result<(), PSP34Error>;
+
+    /// Returns the user of token `id`, or `None` once its rental expired.
+    #[ink(message)]
+    fn user_of(&self, id: Id) -> Option<AccountId>;
+
+    /// Returns the timestamp the rental of token `id` ends at, 0 if none.
+    #[ink(message)]
+    fn user_expires(&self, id: Id) -> u64;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Capped {
+    #[ink(message)]
+    fn max_supply(&self) -> u64;
+
+    #[ink(message)]
+    fn remaining_supply(&self) -> u64;
+
+    /// Lowers the max supply, never below the number of tokens minted so far.
+    #[ink(message)]
+    fn set_max_supply(&mut self, max_supply: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn max_mint_per_wallet(&self) -> Option<u32>;
+
+    /// Sets how many tokens a single account can mint, `None` for no limit.
+    #[ink(message)]
+    fn set_max_mint_per_wallet(&mut self, limit: Option<u32>) -> Result<(), PSP34Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Launchpad {
+    /// Adds a mint phase open from `start_time` until `end_time`.
+    #[ink(message)]
+    fn add_mint_phase(
+        &mut self,
+        start_time: u64,
+        end_time: u64,
+        price: u128,
+        cap: u64,
+        max_per_wallet: Option<u32>,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn update_mint_phase(
+        &mut self,
+        phase_id: u32,
+        start_time: u64,
+        end_time: u64,
+        price: u128,
+        cap: u64,
+        max_per_wallet: Option<u32>,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_mint_phase(&self, phase_id: u32) -> Option<MintPhase>;
+
+    #[ink(message)]
+    fn get_mint_phase_count(&self) -> u32;
+
+    #[ink(message)]
+    fn get_active_mint_phase(&self) -> Option<u32>;
+
+    #[ink(message)]
+    fn get_minted_in_phase(&self, phase_id: u32, account: AccountId) -> u32;
+
+    /// Restricts phase `phase_id` to the accounts of the allowlist tree with
+    /// the given root. `None` opens the phase to everyone.
+    #[ink(message)]
+    fn set_mint_phase_merkle_root(
+        &mut self,
+        phase_id: u32,
+        merkle_root: Option<Hash>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Mints `amount` tokens to the caller for the price of the active phase.
+    #[ink(message, payable)]
+    fn public_mint(&mut self, amount: u32) -> Result<(), PSP34Error>;
+
+    /// Mints `amount` tokens to the caller in the active allowlist phase.
+    /// `proof` shows that `(caller, max_quantity)` is on the allowlist.
+    #[ink(message, payable)]
+    fn allowlist_mint(
+        &mut self,
+        amount: u32,
+        max_quantity: u32,
+        proof: Vec<Hash>,
+    ) -> Result<(), PSP34Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Reveal {
+    /// Sets the URI returned for every token until the collection is revealed.
+    #[ink(message)]
+    fn set_pre_reveal_uri(&mut self, uri: Option<String>) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_pre_reveal_uri(&self) -> Option<String>;
+
+    /// Commits to the hash of the final metadata. It has to be called before
+    /// the first token is minted and can not be changed afterwards.
+    #[ink(message)]
+    fn set_provenance_hash(&mut self, provenance_hash: [u8; 32]) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_provenance_hash(&self) -> Option<[u8; 32]>;
+
+    /// Sets the final base URI and a starting index derived from the provenance
+    /// hash, `offset_seed` and the current block. It can be called only once,
+    /// after `set_provenance_hash`.
+    #[ink(message)]
+    fn reveal(&mut self, base_uri: String, offset_seed: u64) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn is_revealed(&self) -> bool;
+
+    #[ink(message)]
+    fn get_starting_index(&self) -> u64;
+}
+
+#[ink::trait_definition]
+pub trait PSP34Royalty {
+    /// Returns the royalty receiver for token `id` and the royalty amount
+    /// due for a sale at `sale_price`.
+    #[ink(message)]
+    fn royalty_info(&self, id: Id, sale_price: u128) -> (AccountId, u128);
+
+    /// Sets the royalty applied to every token without its own royalty.
+    #[ink(message)]
+    fn set_default_royalty(&mut self, receiver: AccountId, basis_points: u16) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn delete_default_royalty(&mut self) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn set_token_royalty(
+        &mut self,
+        id: Id,
+        receiver: AccountId,
+        basis_points: u16,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn reset_token_royalty(&mut self, id: Id) -> Result<(), PSP34Error>;
 }
 
 #[ink::trait_definition]
 pub trait PSP34Metadata {
+    /// Returns attribute `key` of token `id`. Collection level attributes are
+    /// read with the `collection_id()` of the contract as `id`.
     #[ink(message)]
     fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>>;
 }
 
+#[ink::trait_definition]
+pub trait PSP34CollectionMetadata {
+    #[ink(message)]
+    fn get_collection_metadata(&self) -> CollectionMetadata;
+
+    /// Replaces the name, symbol, description, external URL and image of the
+    /// collection.
+    #[ink(message)]
+    fn set_collection_metadata(&mut self, collection: CollectionMetadata) -> Result<(), PSP34Error>;
+}
+
+/// Paginated listing of tokens, available with and without the `enumerable`
+/// feature.
+#[ink::trait_definition]
+pub trait PSP34Listing {
+    /// Returns at most `limit` tokens owned by `owner`, starting at index `offset`
+    /// of the `balance_of(owner)` tokens it holds.
+    #[ink(message)]
+    fn tokens_of_owner(&self, owner: AccountId, offset: u128, limit: u32) -> Vec<Id>;
+
+    /// Returns the tokens minted at positions `offset` to `offset + limit`, in
+    /// mint order. Burned tokens are skipped, so a page can hold fewer than
+    /// `limit` tokens; the last page starts before `minted_count`.
+    #[ink(message)]
+    fn all_tokens(&self, offset: u128, limit: u32) -> Vec<Id>;
+
+    /// Returns the number of tokens ever minted, burned ones included.
+    #[ink(message)]
+    fn minted_count(&self) -> u128;
+
+    /// Returns the owner of each of `ids`.
+    #[ink(message)]
+    fn owners_of(&self, ids: Vec<Id>) -> Vec<Option<AccountId>>;
+}
+
 #[cfg(feature = "enumerable")]
 #[ink::trait_definition]
 pub trait PSP34Enumerable {
@@ -104,3 +416,12 @@ pub trait Ownable {
     #[ink(message)]
     fn transfer_ownership(&mut self, new_owner: Option<AccountId>) -> Result<(), PSP34Error>;
 }
+
+#[ink::trait_definition]
+pub trait AdminTrait {
+    #[ink(message)]
+    fn withdraw_fee(&mut self, value: u128, receiver: AccountId) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn get_balance(&mut self) -> Result<u128, PSP34Error>;
+}
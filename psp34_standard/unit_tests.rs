@@ -64,11 +64,11 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Token 1 does not exists.
-                assert_eq!(token.owner_of(Id::U8(1)), None);
+                assert_eq!(token.owner_of(Id::U64(1)), None);
                 // Alice does not owns tokens.
                 assert_eq!(token.balance_of(accounts.alice), 0);
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns 1 token.
                 assert_eq!(token.balance_of(accounts.alice), 1);
             }
@@ -79,16 +79,20 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // The first Transfer event takes place
                 assert_eq!(1, recorded_events().count());
                 // Alice owns 1 token.
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Alice owns token Id 1.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
                 // Cannot create  token Id if it exists.
                 // Bob cannot own token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Err(PSP34Error::TokenExists));
+                assert_eq!(token.data.mint(accounts.bob, Id::U64(1)).err(), Some(PSP34Error::TokenExists));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
+                // The next mint creates token Id 2.
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(2)), Some(accounts.alice));
             }
 
             #[ink::test]
@@ -97,7 +101,7 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1 for Alice
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns token 1
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Bob does not owns any token
@@ -106,7 +110,7 @@ macro_rules! tests {
                 assert_eq!(1, recorded_events().count());
                 // Alice transfers token 1 to Bob
                 assert_eq!(
-                    token.transfer(accounts.bob, Id::U8(1), vec![u8::default()]),
+                    token.transfer(accounts.bob, Id::U64(1), vec![u8::default()]),
                     Ok(())
                 );
                 // The second Transfer event takes place
@@ -122,7 +126,7 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1 for Alice
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns token 1
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Bob does not owns any token
@@ -131,14 +135,14 @@ macro_rules! tests {
                 assert_eq!(1, recorded_events().count());
                 // Alice transfers token 1 to Bob
                 assert_eq!(
-                    token.transfer(accounts.bob, Id::U8(1), vec![u8::default()]),
+                    token.transfer(accounts.bob, Id::U64(1), vec![u8::default()]),
                     Ok(())
                 );                
                 // The second Transfer event takes place
                 assert_eq!(2, recorded_events().count());
                 // The correct event emited
                 let events = decode_events(start);
-                assert_transfer(&events[1], accounts.alice, accounts.bob, Id::U8(1));
+                assert_transfer(&events[1], accounts.alice, accounts.bob, Id::U64(1));
             }
 
             #[ink::test]
@@ -148,22 +152,23 @@ macro_rules! tests {
                 let mut token = $constructor();
                 // Transfer token fails if it does not exists.
                 assert_eq!(
-                    token.transfer(accounts.bob, Id::U8(2), vec![u8::default()]),
+                    token.transfer(accounts.bob, Id::U64(2), vec![u8::default()]),
                     Err(PSP34Error::TokenNotExists)
                 );
                 // Token Id 2 does not exists.
-                assert_eq!(token.owner_of(Id::U8(2)), None);
-                // Create token Id 2.
-                assert_eq!(token.mint(Id::U8(2)), Ok(()));
-                // Alice owns 1 token.
-                assert_eq!(token.balance_of(accounts.alice), 1);
+                assert_eq!(token.owner_of(Id::U64(2)), None);
+                // Create token Id 1 and 2.
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                // Alice owns 2 tokens.
+                assert_eq!(token.balance_of(accounts.alice), 2);
                 // Token Id 2 is owned by Alice.
-                assert_eq!(token.owner_of(Id::U8(2)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U64(2)), Some(accounts.alice));
                 // Set Bob as caller
                 set_caller(accounts.bob);
                 // Bob cannot transfer not owned tokens.
                 assert_eq!(
-                    token.transfer(accounts.eve, Id::U8(2), vec![u8::default()]),
+                    token.transfer(accounts.eve, Id::U64(2), vec![u8::default()]),
                     Err(PSP34Error::NotApproved)
                 );
             }
@@ -174,20 +179,20 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Token Id 1 is owned by Alice.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
                 // Approve token Id 1 transfer for Bob on behalf of Alice.
-                assert_eq!(token.approve(accounts.bob, Some(Id::U8(1)), true), Ok(()));
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
                 // Set Bob as caller
                 set_caller(accounts.bob);
                 // Bob transfers token Id 1 from Alice to Eve.
                 assert_eq!(
-                    token.transfer(accounts.eve, Id::U8(1), vec![u8::default()]),
+                    token.transfer(accounts.eve, Id::U64(1), vec![u8::default()]),
                     Ok(())
                 );
                 // TokenId 3 is owned by Eve.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.eve));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.eve));
                 // Alice does not owns tokens.
                 assert_eq!(token.balance_of(accounts.alice), 0);
                 // Bob does not owns tokens.
@@ -203,15 +208,15 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Token Id 1 is owned by Alice.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
                 // Approve token Id 1 transfer for Bob on behalf of Alice.
-                assert_eq!(token.approve(accounts.bob, Some(Id::U8(1)), true), Ok(()));
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
                 // The event approve event takes place
                 let events = decode_events(start);
                 assert_eq!(events.len(), 2);
-                assert_approval(&events[1], accounts.alice, accounts.bob, Some(Id::U8(1)), true);
+                assert_approval(&events[1], accounts.alice, accounts.bob, Some(Id::U64(1)), true);
             }
 
             #[ink::test]
@@ -220,9 +225,9 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Create token Id 2.
-                assert_eq!(token.mint(Id::U8(2)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns 2 tokens.
                 assert_eq!(token.balance_of(accounts.alice), 2);
                 // Approve all tokens transfer for Bob on behalf of Alice.
@@ -233,16 +238,16 @@ macro_rules! tests {
                 set_caller(accounts.bob);
                 // Bob transfers token Id 1 from Alice to Eve.
                 assert_eq!(
-                    token.transfer(accounts.eve, Id::U8(1), vec![u8::default()]),
+                    token.transfer(accounts.eve, Id::U64(1), vec![u8::default()]),
                     Ok(())
                 );
                 // TokenId 1 is owned by Eve.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.eve));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.eve));
                 // Alice owns 1 token.
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Bob transfers token Id 2 from Alice to Eve.
                 assert_eq!(
-                    token.transfer(accounts.eve, Id::U8(2), vec![u8::default()]),
+                    token.transfer(accounts.eve, Id::U64(2), vec![u8::default()]),
                     Ok(())
                 );
                 // Bob does not own tokens.
@@ -262,9 +267,9 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Create token Id 2.
-                assert_eq!(token.mint(Id::U8(2)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns 2 tokens.
                 assert_eq!(token.balance_of(accounts.alice), 2);
                 // Approve all tokens transfer for Bob on behalf of Alice.
@@ -272,7 +277,7 @@ macro_rules! tests {
                 // Bob is an approved operator for Alice
                 assert!(token.allowance(accounts.alice, accounts.bob, None));
                 // Cannot revoke approval for a single token for Bob
-                assert_eq!(token.approve(accounts.bob, Some(Id::U8(1)), false),
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), false),
                     Err(PSP34Error::Custom(String::from(
                         "Cannot revoke approval for a single token, when the operator has approval for all tokens.")))
                 );
@@ -284,7 +289,7 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1.
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns 1 token.
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Bob does not owns tokens.
@@ -295,7 +300,7 @@ macro_rules! tests {
                 set_caller(accounts.eve);
                 // Eve is not an approved operator by Alice.
                 assert_eq!(
-                    token.transfer(accounts.frank, Id::U8(1), vec![u8::default()]),
+                    token.transfer(accounts.frank, Id::U64(1), vec![u8::default()]),
                     Err(PSP34Error::NotApproved)
                 );
                 // Alice owns 1 token.
@@ -312,17 +317,17 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1 for Alice
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Alice owns 1 token.
                 assert_eq!(token.balance_of(accounts.alice), 1);
                 // Alice owns token Id 1.
-                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
                 // Destroy token Id 1.
-                assert_eq!(token.burn(accounts.alice, Id::U8(1)), Ok(()));
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Ok(()));
                 // Alice does not owns tokens.
                 assert_eq!(token.balance_of(accounts.alice), 0);
                 // Token Id 1 does not exists
-                assert_eq!(token.owner_of(Id::U8(1)), None);
+                assert_eq!(token.owner_of(Id::U64(1)), None);
             }
 
             #[ink::test]
@@ -332,7 +337,7 @@ macro_rules! tests {
                 let mut token = $constructor();
                 // Try burning a non existent token
                 assert_eq!(
-                    token.burn(accounts.alice, Id::U8(1)),
+                    token.burn(accounts.alice, Id::U64(1)),
                     Err(PSP34Error::TokenNotExists)
                 );
             }
@@ -343,13 +348,1120 @@ macro_rules! tests {
                 // Create a new contract instance.
                 let mut token = $constructor();
                 // Create token Id 1 for Alice
-                assert_eq!(token.mint(Id::U8(1)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
                 // Try burning this token with a different account
                 set_caller(accounts.eve);
                 assert_eq!(
-                    token.burn(accounts.alice, Id::U8(1)),
+                    token.burn(accounts.alice, Id::U64(1)),
+                    Err(PSP34Error::NotApproved)
+                );
+            }
+
+            #[ink::test]
+            fn burn_by_approved_operator_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Create token Id 1 for Alice
+                assert_eq!(token.mint(), Ok(()));
+                // Approve token Id 1 for Bob on behalf of Alice.
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
+                // Bob destroys token Id 1 of Alice.
+                set_caller(accounts.bob);
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Ok(()));
+                // Alice does not owns tokens.
+                assert_eq!(token.balance_of(accounts.alice), 0);
+                // Token Id 1 does not exists
+                assert_eq!(token.owner_of(Id::U64(1)), None);
+            }
+
+            #[ink::test]
+            fn burn_fails_wrong_account() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Create token Id 1 for Alice
+                assert_eq!(token.mint(), Ok(()));
+                // Token Id 1 is not owned by Bob.
+                assert_eq!(
+                    token.burn(accounts.bob, Id::U64(1)),
+                    Err(PSP34Error::NotTokenOwner)
+                );
+                // Alice still owns token Id 1.
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
+            }
+
+            #[ink::test]
+            fn burn_emits_event() {
+                let accounts = default_accounts::<E>();
+                let start = recorded_events().count();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Create token Id 1 for Alice
+                assert_eq!(token.mint(), Ok(()));
+                // Destroy token Id 1.
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Ok(()));
+                // The Transfer event to nobody takes place
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                if let Event::Transfer(Transfer { from, to, id }) = &events[1] {
+                    assert_eq!(*from, Some(accounts.alice), "Transfer event: 'from' mismatch");
+                    assert_eq!(*to, None, "Transfer event: 'to' mismatch");
+                    assert_eq!(*id, Id::U64(1), "Transfer event: 'id' mismatch");
+                } else {
+                    panic!("Event is not Transfer")
+                }
+            }
+
+            #[ink::test]
+            fn burn_clears_approvals_and_attributes() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Create token Id 1 with attributes for Alice
+                assert_eq!(
+                    token.mint_with_attributes(vec![(String::from("name"), String::from("Token 1"))]),
+                    Ok(())
+                );
+                // Approve token Id 1 for Bob and Charlie on behalf of Alice.
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
+                assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), true), Ok(()));
+                // Destroy token Id 1.
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Ok(()));
+                // No approvals for token Id 1 are left.
+                assert!(!token.allowance(accounts.alice, accounts.bob, Some(Id::U64(1))));
+                assert!(!token.allowance(accounts.alice, accounts.charlie, Some(Id::U64(1))));
+                // No attributes for token Id 1 are left.
+                assert_eq!(token.get_attribute(Id::U64(1), String::from("name").into_bytes()), None);
+            }
+
+            #[ink::test]
+            fn max_supply_caps_minting() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                let max_supply = token.max_supply();
+                // Mint the whole collection.
+                for _ in 0..max_supply {
+                    assert_eq!(token.mint(), Ok(()));
+                }
+                assert_eq!(token.remaining_supply(), 0);
+                // Cannot mint beyond the max supply.
+                assert_eq!(token.mint(), Err(PSP34Error::MaxSupplyExceeded));
+                assert_eq!(token.total_supply(), max_supply as u128);
+            }
+
+            #[ink::test]
+            fn set_max_supply_only_lowers() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                let max_supply = token.max_supply();
+                // Create token Id 1 and 2.
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                // Cannot raise the max supply.
+                assert_eq!(token.set_max_supply(max_supply + 1), Err(PSP34Error::InvalidInput));
+                // Cannot lower it below the minted tokens.
+                assert_eq!(token.set_max_supply(1), Err(PSP34Error::InvalidInput));
+                // Only the owner can lower it.
+                set_caller(accounts.bob);
+                assert_eq!(token.set_max_supply(2), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.set_max_supply(2), Ok(()));
+                assert_eq!(token.max_supply(), 2);
+                assert_eq!(token.remaining_supply(), 0);
+                assert_eq!(token.mint(), Err(PSP34Error::MaxSupplyExceeded));
+            }
+
+            #[ink::test]
+            fn max_mint_per_wallet_works() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // No limit by default.
+                assert_eq!(token.max_mint_per_wallet(), None);
+                // Limit every wallet to 2 tokens.
+                assert_eq!(token.set_max_mint_per_wallet(Some(2)), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.mint(), Err(PSP34Error::MintLimitExceeded));
+                // Lifting the limit allows minting again.
+                assert_eq!(token.set_max_mint_per_wallet(None), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+            }
+
+            #[ink::test]
+            fn public_mint_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Open a phase selling tokens for 10 each.
+                assert_eq!(token.add_mint_phase(1000, 2000, 10, 5, Some(2)), Ok(()));
+                set_block_timestamp::<E>(1500);
+                assert_eq!(token.get_active_mint_phase(), Some(1));
+                // Bob mints 2 tokens paying for both.
+                set_caller(accounts.bob);
+                set_value_transferred::<E>(20);
+                assert_eq!(token.public_mint(2), Ok(()));
+                assert_eq!(token.balance_of(accounts.bob), 2);
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.bob));
+                assert_eq!(token.owner_of(Id::U64(2)), Some(accounts.bob));
+                assert_eq!(token.get_minted_in_phase(1, accounts.bob), 2);
+                assert_eq!(token.get_mint_phase(1).unwrap().minted, 2);
+            }
+
+            #[ink::test]
+            fn public_mint_requires_exact_fee() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.add_mint_phase(1000, 2000, 10, 5, None), Ok(()));
+                set_block_timestamp::<E>(1000);
+                set_caller(accounts.bob);
+                set_value_transferred::<E>(15);
+                assert_eq!(token.public_mint(2), Err(PSP34Error::InvalidFee));
+            }
+
+            #[ink::test]
+            fn public_mint_outside_phase_fails() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.add_mint_phase(1000, 2000, 10, 5, None), Ok(()));
+                // Phases can not overlap.
+                assert_eq!(token.add_mint_phase(1500, 2500, 10, 5, None), Err(PSP34Error::InvalidInput));
+                // Only the owner adds phases.
+                set_caller(accounts.bob);
+                assert_eq!(token.add_mint_phase(3000, 4000, 10, 5, None), Err(PSP34Error::CallerIsNotOwner));
+                // The phase is closed at its end time.
+                set_block_timestamp::<E>(2000);
+                set_value_transferred::<E>(10);
+                assert_eq!(token.get_active_mint_phase(), None);
+                assert_eq!(token.public_mint(1), Err(PSP34Error::NoActiveMintPhase));
+            }
+
+            #[ink::test]
+            fn public_mint_respects_phase_limits() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.add_mint_phase(0, 2000, 10, 3, Some(2)), Ok(()));
+                set_caller(accounts.bob);
+                set_value_transferred::<E>(30);
+                // Bob can mint at most 2 tokens in the phase.
+                assert_eq!(token.public_mint(3), Err(PSP34Error::MintLimitExceeded));
+                set_value_transferred::<E>(20);
+                assert_eq!(token.public_mint(2), Ok(()));
+                // Only one token is left in the phase.
+                set_caller(accounts.charlie);
+                assert_eq!(token.public_mint(2), Err(PSP34Error::PhaseCapExceeded));
+                set_value_transferred::<E>(10);
+                assert_eq!(token.public_mint(1), Ok(()));
+                assert_eq!(token.total_supply(), 3);
+            }
+
+            #[ink::test]
+            fn withdraw_fee_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                set_account_balance::<E>(callee::<E>(), 100);
+                // Only the owner can withdraw.
+                set_caller(accounts.bob);
+                assert_eq!(token.withdraw_fee(50, accounts.bob), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.withdraw_fee(101, accounts.bob), Err(PSP34Error::NotEnoughBalance));
+                let bob_balance = get_account_balance::<E>(accounts.bob).unwrap();
+                assert_eq!(token.withdraw_fee(40, accounts.bob), Ok(()));
+                assert_eq!(token.get_balance(), Ok(60));
+                assert_eq!(get_account_balance::<E>(accounts.bob).unwrap(), bob_balance + 40);
+            }
+
+            #[ink::test]
+            fn allowlist_mint_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Allowlist: Alice can mint 2, Bob 1 and Charlie 3 tokens.
+                let alice_leaf = $crate::merkle::leaf(accounts.alice, 2);
+                let bob_leaf = $crate::merkle::leaf(accounts.bob, 1);
+                let charlie_leaf = $crate::merkle::leaf(accounts.charlie, 3);
+                let node = $crate::merkle::hash_pair(&alice_leaf, &bob_leaf);
+                let root = $crate::merkle::hash_pair(&node, &charlie_leaf);
+                assert_eq!(token.add_mint_phase(0, 2000, 10, 10, None), Ok(()));
+                assert_eq!(token.set_mint_phase_merkle_root(1, Some(root)), Ok(()));
+                // Bob mints his single token.
+                set_caller(accounts.bob);
+                set_value_transferred::<E>(10);
+                assert_eq!(token.allowlist_mint(1, 1, vec![alice_leaf, charlie_leaf]), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.bob));
+                assert_eq!(token.get_minted_in_phase(1, accounts.bob), 1);
+                // Bob can not mint more than allowlisted.
+                assert_eq!(
+                    token.allowlist_mint(1, 1, vec![alice_leaf, charlie_leaf]),
+                    Err(PSP34Error::MintLimitExceeded)
+                );
+                // Charlie mints 3 tokens.
+                set_caller(accounts.charlie);
+                set_value_transferred::<E>(30);
+                assert_eq!(token.allowlist_mint(3, 3, vec![node]), Ok(()));
+                assert_eq!(token.balance_of(accounts.charlie), 3);
+            }
+
+            #[ink::test]
+            fn allowlist_mint_with_invalid_proof_fails() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                let alice_leaf = $crate::merkle::leaf(accounts.alice, 2);
+                let bob_leaf = $crate::merkle::leaf(accounts.bob, 1);
+                let root = $crate::merkle::hash_pair(&alice_leaf, &bob_leaf);
+                assert_eq!(token.add_mint_phase(0, 2000, 10, 10, None), Ok(()));
+                assert_eq!(token.set_mint_phase_merkle_root(1, Some(root)), Ok(()));
+                set_caller(accounts.bob);
+                set_value_transferred::<E>(20);
+                // Bob can not claim a higher quantity than allowlisted.
+                assert_eq!(
+                    token.allowlist_mint(2, 2, vec![alice_leaf]),
+                    Err(PSP34Error::NotAllowlisted)
+                );
+                // Eve is not on the allowlist.
+                set_caller(accounts.eve);
+                assert_eq!(
+                    token.allowlist_mint(1, 1, vec![alice_leaf]),
+                    Err(PSP34Error::NotAllowlisted)
+                );
+                // The public can not mint in an allowlist phase.
+                assert_eq!(token.public_mint(1), Err(PSP34Error::NotAllowlisted));
+            }
+
+            #[ink::test]
+            fn mint_to_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Mint token Id 1 for Bob.
+                assert_eq!(token.mint_to(accounts.bob), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.bob));
+                assert_eq!(token.balance_of(accounts.alice), 0);
+                // Only the owner can mint.
+                set_caller(accounts.bob);
+                assert_eq!(token.mint_to(accounts.bob), Err(PSP34Error::CallerIsNotOwner));
+            }
+
+            #[ink::test]
+            fn batch_mint_to_works() {
+                let accounts = default_accounts::<E>();
+                let start = recorded_events().count();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(
+                    token.batch_mint_to(vec![accounts.bob, accounts.charlie, accounts.bob]),
+                    Ok(())
+                );
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.bob));
+                assert_eq!(token.owner_of(Id::U64(2)), Some(accounts.charlie));
+                assert_eq!(token.owner_of(Id::U64(3)), Some(accounts.bob));
+                assert_eq!(token.get_last_token_id(), 3);
+                // A Transfer event for every token takes place
+                assert_eq!(decode_events(start).len(), 3);
+                // Minting beyond the max supply fails for the whole batch.
+                let recipients = vec![accounts.eve; token.remaining_supply() as usize + 1];
+                assert_eq!(token.batch_mint_to(recipients), Err(PSP34Error::MaxSupplyExceeded));
+            }
+
+            #[ink::test]
+            fn batch_mint_with_attributes_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(
+                    token.batch_mint_with_attributes(vec![
+                        (accounts.bob, vec![(String::from("name"), String::from("Bob's"))]),
+                        (accounts.charlie, vec![(String::from("name"), String::from("Charlie's"))]),
+                    ]),
+                    Ok(())
+                );
+                assert_eq!(token.owner_of(Id::U64(2)), Some(accounts.charlie));
+                assert_eq!(
+                    token.get_attributes(Id::U64(1), vec![String::from("name")]),
+                    vec![String::from("Bob's")]
+                );
+                assert_eq!(
+                    token.get_attributes(Id::U64(2), vec![String::from("name")]),
+                    vec![String::from("Charlie's")]
+                );
+            }
+
+            #[ink::test]
+            fn token_uri_works() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.set_base_uri(String::from("ipfs://base/")), Ok(()));
+                assert_eq!(token.token_uri(Id::U64(1)), String::from("ipfs://base/1.json"));
+                assert_eq!(token.token_uri(Id::U64(42)), String::from("ipfs://base/42.json"));
+                assert_eq!(token.token_uri(Id::Bytes(vec![1, 171])), String::from("ipfs://base/01ab.json"));
+                // The suffix is configurable.
+                assert_eq!(token.set_uri_suffix(String::new()), Ok(()));
+                assert_eq!(token.token_uri(Id::U64(42)), String::from("ipfs://base/42"));
+                // A placeholder in the base URI is replaced by the id.
+                assert_eq!(token.set_base_uri(String::from("https://api/{id}/meta")), Ok(()));
+                assert_eq!(token.token_uri(Id::U64(7)), String::from("https://api/7/meta"));
+            }
+
+            #[ink::test]
+            fn set_token_uri_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.set_base_uri(String::from("ipfs://base/")), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                // Token Id 1 gets its own URI.
+                assert_eq!(token.set_token_uri(Id::U64(1), Some(String::from("ar://one"))), Ok(()));
+                assert_eq!(token.token_uri(Id::U64(1)), String::from("ar://one"));
+                assert_eq!(token.token_uri(Id::U64(2)), String::from("ipfs://base/2.json"));
+                // Only the owner can set it.
+                set_caller(accounts.bob);
+                assert_eq!(token.set_token_uri(Id::U64(1), None), Err(PSP34Error::CallerIsNotOwner));
+                // Removing the URI falls back to the base URI.
+                set_caller(accounts.alice);
+                assert_eq!(token.set_token_uri(Id::U64(1), None), Ok(()));
+                assert_eq!(token.token_uri(Id::U64(1)), String::from("ipfs://base/1.json"));
+            }
+
+            #[ink::test]
+            fn on_chain_metadata_works() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(
+                    token.mint_with_attributes(vec![
+                        (String::from("name"), String::from("Dragon \"One\"")),
+                        (String::from("Color"), String::from("Red")),
+                        (String::from("image"), String::from("ipfs://img")),
+                    ]),
+                    Ok(())
+                );
+                assert_eq!(token.set_on_chain_metadata(true), Ok(()));
+                assert!(token.is_on_chain_metadata());
+                // {"name":"Dragon \"One\"","description":"","image":"ipfs://img",
+                //  "attributes":[{"trait_type":"Color","value":"Red"}]}
+                assert_eq!(
+                    token.token_uri(Id::U64(1)),
+                    String::from("data:application/json;base64,eyJuYW1lIjoiRHJhZ29uIFwiT25lXCIiLCJkZXNjcmlwdGlvbiI6IiIsImltYWdlIjoiaXBmczovL2ltZyIsImF0dHJpYnV0ZXMiOlt7InRyYWl0X3R5cGUiOiJDb2xvciIsInZhbHVlIjoiUmVkIn1dfQ==")
+                );
+            }
+
+            #[ink::test]
+            fn reveal_works() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.set_pre_reveal_uri(Some(String::from("ipfs://hidden.json"))), Ok(()));
+                assert_eq!(token.set_provenance_hash([1; 32]), Ok(()));
+                // The provenance hash is committed once.
+                assert_eq!(token.set_provenance_hash([2; 32]), Err(PSP34Error::ProvenanceHashAlreadySet));
+                assert_eq!(token.mint(), Ok(()));
+                set_block_timestamp::<E>(1_000);
+                // Every token shows the placeholder before the reveal.
+                assert_eq!(token.token_uri(Id::U64(1)), String::from("ipfs://hidden.json"));
+                assert_eq!(token.token_uri(Id::U64(5)), String::from("ipfs://hidden.json"));
+                assert_eq!(token.reveal(String::from("ipfs://real/"), 7), Ok(()));
+                assert!(token.is_revealed());
+                // Tokens are shifted by the starting index.
+                let max_supply = token.max_supply();
+                let starting_index = token.get_starting_index();
+                assert!(starting_index < max_supply);
+                let expected = (starting_index % max_supply) + 1;
+                assert_eq!(
+                    token.token_uri(Id::U64(1)),
+                    String::from("ipfs://real/") + &expected.to_string() + ".json"
+                );
+                assert_eq!(token.get_provenance_hash(), Some([1; 32]));
+            }
+
+            #[ink::test]
+            fn revealed_state_is_immutable() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Only the owner can reveal.
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.reveal(String::from("ipfs://real/"), 7),
+                    Err(PSP34Error::CallerIsNotOwner)
+                );
+                set_caller(accounts.alice);
+                // The final metadata has to be committed to first.
+                assert_eq!(
+                    token.reveal(String::from("ipfs://real/"), 7),
+                    Err(PSP34Error::ProvenanceHashNotSet)
+                );
+                // It can not be committed once minting started.
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.set_provenance_hash([1; 32]), Err(PSP34Error::MintingStarted));
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Ok(()));
+                assert_eq!(token.set_provenance_hash([1; 32]), Err(PSP34Error::MintingStarted));
+                token = $constructor();
+                assert_eq!(token.set_provenance_hash([1; 32]), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.reveal(String::from("ipfs://real/"), 7), Ok(()));
+                assert_eq!(
+                    token.reveal(String::from("ipfs://other/"), 7),
+                    Err(PSP34Error::AlreadyRevealed)
+                );
+                assert_eq!(
+                    token.set_base_uri(String::from("ipfs://other/")),
+                    Err(PSP34Error::AlreadyRevealed)
+                );
+                assert_eq!(token.set_provenance_hash([1; 32]), Err(PSP34Error::AlreadyRevealed));
+                assert_eq!(
+                    token.set_pre_reveal_uri(Some(String::from("ipfs://hidden.json"))),
+                    Err(PSP34Error::AlreadyRevealed)
+                );
+                // Token URIs can not be rewritten either.
+                assert_eq!(
+                    token.set_token_uri(Id::U64(1), Some(String::from("ipfs://other/1"))),
+                    Err(PSP34Error::AlreadyRevealed)
+                );
+                assert_eq!(token.set_on_chain_metadata(true), Err(PSP34Error::AlreadyRevealed));
+                assert_eq!(
+                    token.set_uri_suffix(String::from(".txt")),
+                    Err(PSP34Error::AlreadyRevealed)
+                );
+                assert!(token.token_uri(Id::U64(1)).starts_with("ipfs://real/"));
+            }
+
+            #[ink::test]
+            fn royalty_info_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // No royalty by default.
+                assert_eq!(token.royalty_info(Id::U64(1), 1000), (AccountId::from([0u8; 32]), 0));
+                // 5% to Bob for the whole collection.
+                assert_eq!(token.set_default_royalty(accounts.bob, 500), Ok(()));
+                assert_eq!(token.royalty_info(Id::U64(1), 1000), (accounts.bob, 50));
+                assert_eq!(token.royalty_info(Id::Bytes(vec![1, 2]), 1000), (accounts.bob, 50));
+                // 10% to Charlie for a single token.
+                assert_eq!(token.set_token_royalty(Id::Bytes(vec![1, 2]), accounts.charlie, 1000), Ok(()));
+                assert_eq!(token.royalty_info(Id::Bytes(vec![1, 2]), 1000), (accounts.charlie, 100));
+                assert_eq!(token.royalty_info(Id::U64(1), u128::MAX), (accounts.bob, u128::MAX / 20));
+                // Resetting the token royalty falls back to the collection one.
+                assert_eq!(token.reset_token_royalty(Id::Bytes(vec![1, 2])), Ok(()));
+                assert_eq!(token.royalty_info(Id::Bytes(vec![1, 2]), 1000), (accounts.bob, 50));
+                assert_eq!(token.delete_default_royalty(), Ok(()));
+                assert_eq!(token.royalty_info(Id::U64(1), 1000), (AccountId::from([0u8; 32]), 0));
+            }
+
+            #[ink::test]
+            fn set_royalty_validates_input() {
+                let accounts = default_accounts::<E>();
+                let start = recorded_events().count();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                // Royalty can not exceed the sale price.
+                assert_eq!(token.set_default_royalty(accounts.bob, 10_001), Err(PSP34Error::InvalidInput));
+                // Only the owner sets royalties.
+                set_caller(accounts.bob);
+                assert_eq!(token.set_default_royalty(accounts.bob, 500), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.set_token_royalty(Id::U64(1), accounts.bob, 500), Ok(()));
+                // The RoyaltySet event takes place
+                let events = decode_events(start);
+                assert_eq!(events.len(), 1);
+                if let Event::RoyaltySet(RoyaltySet { id, receiver, basis_points }) = &events[0] {
+                    assert_eq!(*id, Some(Id::U64(1)));
+                    assert_eq!(*receiver, Some(accounts.bob));
+                    assert_eq!(*basis_points, 500);
+                } else {
+                    panic!("Event is not RoyaltySet")
+                }
+            }
+
+            #[ink::test]
+            fn transfer_lock_blocks_transfer_and_burn() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                // Bob, e.g. a staking contract, is approved for every token and locks token Id 1.
+                assert_eq!(token.approve(accounts.bob, None, true), Ok(()));
+                set_caller(accounts.bob);
+                assert_eq!(token.lock_transfer(Id::U64(1)), Ok(()));
+                assert_eq!(token.transfer_locker(Id::U64(1)), Some(accounts.bob));
+                // Alice can neither transfer nor burn the token.
+                set_caller(accounts.alice);
+                assert_eq!(
+                    token.transfer(accounts.charlie, Id::U64(1), vec![]),
+                    Err(PSP34Error::IsLockedToken)
+                );
+                assert_eq!(token.burn(accounts.alice, Id::U64(1)), Err(PSP34Error::IsLockedToken));
+                // Only Bob can unlock it.
+                assert_eq!(token.unlock_transfer(Id::U64(1)), Err(PSP34Error::NotApproved));
+                set_caller(accounts.bob);
+                assert_eq!(token.unlock_transfer(Id::U64(1)), Ok(()));
+                set_caller(accounts.alice);
+                assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![]), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.charlie));
+            }
+
+            #[ink::test]
+            fn lock_transfer_requires_approval() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                set_caller(accounts.eve);
+                assert_eq!(token.lock_transfer(Id::U64(1)), Err(PSP34Error::NotApproved));
+                assert_eq!(token.lock_transfer(Id::U64(2)), Err(PSP34Error::TokenNotExists));
+                // An approval for the token alone, e.g. a listing, does not allow locking it.
+                set_caller(accounts.alice);
+                assert_eq!(token.approve(accounts.eve, Some(Id::U64(1)), true), Ok(()));
+                set_caller(accounts.eve);
+                assert_eq!(token.lock_transfer(Id::U64(1)), Err(PSP34Error::NotApproved));
+                // The owner can lock its own token.
+                set_caller(accounts.alice);
+                assert_eq!(token.lock_transfer(Id::U64(1)), Ok(()));
+                assert_eq!(token.lock_transfer(Id::U64(1)), Err(PSP34Error::IsLockedToken));
+            }
+
+            #[ink::test]
+            fn freeze_all_metadata_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                // Only the owner can freeze the collection metadata.
+                set_caller(accounts.bob);
+                assert_eq!(token.freeze_all_metadata(), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.freeze_all_metadata(), Ok(()));
+                assert!(token.is_all_metadata_frozen());
+                assert!(token.is_locked_nft(Id::U64(1)));
+                assert_eq!(
+                    token.set_base_uri(String::from("ipfs://other/")),
+                    Err(PSP34Error::MetadataFrozen)
+                );
+                assert_eq!(
+                    token.set_multiple_attributes(Id::U64(1), vec![(String::from("name"), String::from("x"))]),
+                    Err(PSP34Error::MetadataFrozen)
+                );
+                assert_eq!(
+                    token.remove_attributes(Id::U64(1), vec![String::from("name")]),
+                    Err(PSP34Error::MetadataFrozen)
+                );
+                // New tokens cannot be minted with metadata either.
+                assert_eq!(
+                    token.mint_with_attributes(vec![(String::from("name"), String::from("x"))]),
+                    Err(PSP34Error::MetadataFrozen)
+                );
+                assert_eq!(
+                    token.batch_mint_with_attributes(vec![(
+                        accounts.bob,
+                        vec![(String::from("name"), String::from("x"))]
+                    )]),
+                    Err(PSP34Error::MetadataFrozen)
+                );
+                assert_eq!(token.total_supply(), 1);
+                // Transfers are not affected.
+                assert_eq!(token.transfer(accounts.bob, Id::U64(1), vec![]), Ok(()));
+            }
+
+            #[ink::test]
+            fn receiver_response_policy_works() {
+                use $crate::receiver::check_response;
+                use $crate::PSP34ReceiverError;
+                // The receiver accepted the token.
+                assert_eq!(check_response(Ok(Ok(Ok(())))), Ok(()));
+                // The receiver rejected the token.
+                assert_eq!(
+                    check_response(Ok(Ok(Err(PSP34ReceiverError::TransferRejected(String::from("No")))))),
+                    Err(PSP34Error::SafeTransferCheckFailed(String::from("No")))
+                );
+                // The receiver does not implement `PSP34Receiver`.
+                assert_eq!(check_response(Ok(Err(ink::LangError::CouldNotReadInput))), Ok(()));
+                assert_eq!(check_response(Err(ink::env::Error::NotCallable)), Ok(()));
+                // A trap or any other failure reverts the transfer.
+                assert!(matches!(
+                    check_response(Err(ink::env::Error::CalleeTrapped)),
+                    Err(PSP34Error::SafeTransferCheckFailed(_))
+                ));
+                assert!(matches!(
+                    check_response(Err(ink::env::Error::CalleeReverted)),
+                    Err(PSP34Error::SafeTransferCheckFailed(_))
+                ));
+            }
+
+            #[ink::test]
+            fn transfer_to_accepting_receiver_works() {
+                use $crate::receiver::mock;
+                use psp34_receiver_mock::receiver::Receiver;
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                set_contract::<E>(accounts.charlie);
+                mock::deploy(accounts.charlie, Receiver::new(true));
+                assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![1, 2]), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.charlie));
+                // The receiver is told who sent which token.
+                assert_eq!(
+                    mock::last_received(accounts.charlie),
+                    Some((accounts.alice, accounts.alice, Id::U64(1), vec![1, 2]))
+                );
+            }
+
+            #[ink::test]
+            fn transfer_to_rejecting_receiver_fails() {
+                use $crate::receiver::mock;
+                use psp34_receiver_mock::receiver::Receiver;
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                set_contract::<E>(accounts.charlie);
+                mock::deploy(accounts.charlie, Receiver::new(false));
+                assert_eq!(
+                    token.transfer(accounts.charlie, Id::U64(1), vec![]),
+                    Err(PSP34Error::SafeTransferCheckFailed(String::from(
+                        "Receiver does not accept tokens"
+                    )))
+                );
+                assert_eq!(mock::last_received(accounts.charlie), None);
+            }
+
+            #[ink::test]
+            fn transfer_to_trapping_receiver_fails() {
+                use $crate::receiver::mock;
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                set_contract::<E>(accounts.charlie);
+                mock::set_response(accounts.charlie, || Err(ink::env::Error::CalleeTrapped));
+                assert!(matches!(
+                    token.transfer(accounts.charlie, Id::U64(1), vec![]),
+                    Err(PSP34Error::SafeTransferCheckFailed(_))
+                ));
+            }
+
+            #[ink::test]
+            fn transfer_to_contract_without_receiver_works() {
+                use $crate::receiver::mock;
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                // Contracts which do not implement `PSP34Receiver` accept tokens.
+                set_contract::<E>(accounts.charlie);
+                mock::set_response(accounts.charlie, || Ok(Err(ink::LangError::CouldNotReadInput)));
+                assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![]), Ok(()));
+                set_contract::<E>(accounts.django);
+                mock::set_response(accounts.django, || Err(ink::env::Error::NotCallable));
+                assert_eq!(token.transfer(accounts.django, Id::U64(2), vec![]), Ok(()));
+            }
+
+            #[ink::test]
+            fn contract_can_transfer_to_itself() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), true), Ok(()));
+                // An escrow contract pulling a token is not called back.
+                set_contract::<E>(accounts.charlie);
+                set_caller(accounts.charlie);
+                assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![]), Ok(()));
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.charlie));
+            }
+
+            #[ink::test]
+            fn token_approval_is_replaced_by_new_one() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
+                assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), true), Ok(()));
+                // Only Charlie is approved for token Id 1.
+                assert_eq!(token.get_approved(Id::U64(1)), Some(accounts.charlie));
+                assert!(!token.allowance(accounts.alice, accounts.bob, Some(Id::U64(1))));
+                assert!(token.allowance(accounts.alice, accounts.charlie, Some(Id::U64(1))));
+                // Revoking an account which is not approved changes nothing.
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), false), Ok(()));
+                assert_eq!(token.get_approved(Id::U64(1)), Some(accounts.charlie));
+                assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), false), Ok(()));
+                assert_eq!(token.get_approved(Id::U64(1)), None);
+            }
+
+            #[ink::test]
+            fn transfer_clears_token_approval() {
+                let accounts = default_accounts::<E>();
+                let start = recorded_events().count();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
+                // Alice transfers token Id 1 to Charlie, who gives it back.
+                assert_eq!(token.transfer(accounts.charlie, Id::U64(1), vec![]), Ok(()));
+                assert_eq!(token.get_approved(Id::U64(1)), None);
+                set_caller(accounts.charlie);
+                assert_eq!(token.transfer(accounts.alice, Id::U64(1), vec![]), Ok(()));
+                // The approval of Bob did not come back with the token.
+                assert!(!token.allowance(accounts.alice, accounts.bob, Some(Id::U64(1))));
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.transfer(accounts.bob, Id::U64(1), vec![]),
                     Err(PSP34Error::NotApproved)
                 );
+                // An approved transfer is reported as coming from the owner.
+                set_caller(accounts.alice);
+                assert_eq!(token.approve(accounts.bob, Some(Id::U64(1)), true), Ok(()));
+                set_caller(accounts.bob);
+                assert_eq!(token.transfer(accounts.eve, Id::U64(1), vec![]), Ok(()));
+                let events = decode_events(start);
+                assert_transfer(events.last().unwrap(), accounts.alice, accounts.eve, Id::U64(1));
+            }
+
+            #[cfg(feature = "enumerable")]
+            #[ink::test]
+            fn enumerable_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                for _ in 0..3 {
+                    assert_eq!(token.mint(), Ok(()));
+                }
+                assert_eq!(token.data.token_by_index(2), Ok(Id::U64(3)));
+                assert_eq!(token.data.token_by_index(3), Err(PSP34Error::TokenNotExists));
+                // Transferring token Id 1 moves the last token of Alice into its slot.
+                assert_eq!(token.transfer(accounts.bob, Id::U64(1), vec![]), Ok(()));
+                assert_eq!(token.data.owners_token_by_index(accounts.alice, 0), Ok(Id::U64(3)));
+                assert_eq!(token.data.owners_token_by_index(accounts.alice, 1), Ok(Id::U64(2)));
+                assert_eq!(
+                    token.data.owners_token_by_index(accounts.alice, 2),
+                    Err(PSP34Error::TokenNotExists)
+                );
+                assert_eq!(token.data.owners_token_by_index(accounts.bob, 0), Ok(Id::U64(1)));
+                // Burning removes the token from the collection list.
+                assert_eq!(token.burn(accounts.alice, Id::U64(2)), Ok(()));
+                assert_eq!(token.total_supply(), 2);
+                assert_eq!(token.data.token_by_index(0), Ok(Id::U64(1)));
+                assert_eq!(token.data.token_by_index(1), Ok(Id::U64(3)));
+                assert_eq!(token.data.token_by_index(2), Err(PSP34Error::TokenNotExists));
+                assert_eq!(token.balance_of(accounts.alice), 1);
+                // The only token of Bob goes back to Alice.
+                set_caller(accounts.bob);
+                assert_eq!(token.transfer(accounts.alice, Id::U64(1), vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.bob), 0);
+                assert_eq!(token.data.owners_token_by_index(accounts.alice, 1), Ok(Id::U64(1)));
+            }
+
+            #[ink::test]
+            fn token_listing_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                for _ in 0..5 {
+                    assert_eq!(token.mint(), Ok(()));
+                }
+                assert_eq!(token.transfer(accounts.bob, Id::U64(2), vec![]), Ok(()));
+                assert_eq!(token.burn(accounts.alice, Id::U64(3)), Ok(()));
+                // Pages of the collection follow the mint order and skip burned tokens.
+                assert_eq!(token.minted_count(), 5);
+                assert_eq!(token.all_tokens(0, 2), vec![Id::U64(1), Id::U64(2)]);
+                assert_eq!(token.all_tokens(2, 2), vec![Id::U64(4)]);
+                assert_eq!(token.all_tokens(4, 10), vec![Id::U64(5)]);
+                assert_eq!(token.all_tokens(5, 10), vec![]);
+                // Pages of a single owner, whose last token fills the slot of a removed one.
+                assert_eq!(
+                    token.tokens_of_owner(accounts.alice, 0, 10),
+                    vec![Id::U64(1), Id::U64(5), Id::U64(4)]
+                );
+                assert_eq!(token.tokens_of_owner(accounts.alice, 1, 1), vec![Id::U64(5)]);
+                assert_eq!(token.tokens_of_owner(accounts.alice, 3, 1), vec![]);
+                assert_eq!(token.tokens_of_owner(accounts.bob, 0, 10), vec![Id::U64(2)]);
+                assert_eq!(token.tokens_of_owner(accounts.eve, 0, 10), vec![]);
+                // Owners of several tokens at once.
+                assert_eq!(
+                    token.owners_of(vec![Id::U64(1), Id::U64(2), Id::U64(6)]),
+                    vec![Some(accounts.alice), Some(accounts.bob), None]
+                );
+            }
+
+            #[ink::test]
+            fn remove_attributes_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(
+                    token.mint_with_attributes(vec![
+                        (String::from("name"), String::from("Token 1")),
+                        (String::from("color"), String::from("red")),
+                        (String::from("size"), String::from("big")),
+                    ]),
+                    Ok(())
+                );
+                assert_eq!(
+                    token.get_all_attributes(Id::U64(1)),
+                    vec![
+                        (String::from("name"), String::from("Token 1")),
+                        (String::from("color"), String::from("red")),
+                        (String::from("size"), String::from("big")),
+                    ]
+                );
+                // Setting a known attribute again is not an error.
+                assert_eq!(
+                    token.set_multiple_attributes(Id::U64(1), vec![(String::from("color"), String::from("blue"))]),
+                    Ok(())
+                );
+                assert_eq!(token.get_attribute_count(), 3);
+                // Only the owner can remove attributes.
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.remove_attributes(Id::U64(1), vec![String::from("name")]),
+                    Err(PSP34Error::CallerIsNotOwner)
+                );
+                set_caller(accounts.alice);
+                let start = recorded_events().count();
+                assert_eq!(
+                    token.remove_attributes(Id::U64(1), vec![String::from("name"), String::from("missing")]),
+                    Ok(())
+                );
+                // Only the attribute which was set is reported as removed.
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                assert!(matches!(
+                    &events[0],
+                    Event::AttributeRemoved(AttributeRemoved { id, key })
+                        if *id == Id::U64(1) && key == b"name"
+                ));
+                assert!(matches!(&events[1], Event::MetadataUpdate(MetadataUpdate { id }) if *id == Id::U64(1)));
+                assert_eq!(token.get_attribute(Id::U64(1), String::from("name").into_bytes()), None);
+                assert_eq!(
+                    token.get_all_attributes(Id::U64(1)),
+                    vec![
+                        (String::from("size"), String::from("big")),
+                        (String::from("color"), String::from("blue")),
+                    ]
+                );
+                // Attributes of a frozen token can not be removed.
+                assert_eq!(token.lock(Id::U64(1)), Ok(()));
+                assert_eq!(
+                    token.remove_attributes(Id::U64(1), vec![String::from("size")]),
+                    Err(PSP34Error::IsLockedToken)
+                );
+            }
+
+            #[ink::test]
+            fn raw_attribute_getters_work() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(
+                    token.mint_with_attributes(vec![(String::from("name"), String::from("Token 1"))]),
+                    Ok(())
+                );
+                assert!(token.metadata.set_attribute(Id::U64(1), b"blob".to_vec(), vec![0xff, 0x00]).is_ok());
+                // The string getters can not represent invalid UTF-8.
+                assert_eq!(token.get_attributes(Id::U64(1), vec![String::from("blob")]), vec![String::from("")]);
+                assert_eq!(
+                    token.get_attributes_raw(Id::U64(1), vec![b"blob".to_vec(), b"name".to_vec(), b"missing".to_vec()]),
+                    vec![Some(vec![0xff, 0x00]), Some(b"Token 1".to_vec()), None]
+                );
+                assert_eq!(
+                    token.get_all_attributes_raw(Id::U64(1)),
+                    vec![(b"name".to_vec(), b"Token 1".to_vec()), (b"blob".to_vec(), vec![0xff, 0x00])]
+                );
+                assert_eq!(token.get_attribute_name_raw(1), Some(b"name".to_vec()));
+                assert_eq!(token.get_attribute_name_raw(2), None);
+            }
+
+            #[ink::test]
+            fn collection_metadata_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                let collection_id = token.collection_id();
+                // Name and symbol come from the constructor.
+                assert_eq!(token.get_attribute(collection_id.clone(), b"name".to_vec()), Some(b"Collection".to_vec()));
+                assert_eq!(token.get_attribute(collection_id.clone(), b"symbol".to_vec()), Some(b"COL".to_vec()));
+                assert_eq!(token.get_attribute(collection_id.clone(), b"image".to_vec()), None);
+                // Only the owner can change the collection metadata.
+                let collection = $crate::metadata::CollectionMetadata {
+                    name: Some(String::from("Renamed")),
+                    description: Some(String::from("A collection")),
+                    external_url: Some(String::from("https://example.com")),
+                    image: Some(String::from("ipfs://image")),
+                    ..token.get_collection_metadata()
+                };
+                set_caller(accounts.bob);
+                assert_eq!(token.set_collection_metadata(collection.clone()), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.set_collection_metadata(collection.clone()), Ok(()));
+                assert_eq!(token.get_collection_metadata(), collection);
+                assert_eq!(token.get_attribute(collection_id.clone(), b"name".to_vec()), Some(b"Renamed".to_vec()));
+                assert_eq!(
+                    token.get_attribute(collection_id.clone(), b"external_url".to_vec()),
+                    Some(b"https://example.com".to_vec())
+                );
+                // The base URI is a collection attribute, not one of token Id 0.
+                assert_eq!(token.set_base_uri(String::from("ipfs://base/")), Ok(()));
+                assert_eq!(token.get_attribute(collection_id.clone(), b"baseURI".to_vec()), Some(b"ipfs://base/".to_vec()));
+                assert_eq!(token.get_attribute(Id::U8(0), b"baseURI".to_vec()), None);
+                // Frozen collection metadata can not change.
+                assert_eq!(token.freeze_all_metadata(), Ok(()));
+                assert_eq!(token.set_collection_metadata(collection), Err(PSP34Error::MetadataFrozen));
+            }
+
+            #[ink::test]
+            fn metadata_update_events_work() {
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.set_provenance_hash([1; 32]), Ok(()));
+                assert_eq!(token.mint(), Ok(()));
+                let is_update_of = |event: &Event, id_: Id| {
+                    matches!(event, Event::MetadataUpdate(MetadataUpdate { id }) if *id == id_)
+                };
+                let is_batch_update = |event: &Event| {
+                    matches!(
+                        event,
+                        Event::BatchMetadataUpdate(BatchMetadataUpdate { from_id, to_id })
+                            if *from_id == Id::U64(0) && *to_id == Id::U64(u64::MAX)
+                    )
+                };
+                // Setting several attributes at once reports a single update.
+                let start = recorded_events().count();
+                assert_eq!(
+                    token.set_multiple_attributes(
+                        Id::U64(1),
+                        vec![
+                            (String::from("name"), String::from("Token 1")),
+                            (String::from("color"), String::from("red")),
+                        ]
+                    ),
+                    Ok(())
+                );
+                let events = decode_events(start);
+                assert_eq!(events.len(), 3);
+                assert!(is_update_of(&events[2], Id::U64(1)));
+                // Token URI overrides update a single token.
+                let start = recorded_events().count();
+                assert_eq!(token.set_token_uri(Id::U64(1), Some(String::from("ipfs://one"))), Ok(()));
+                let events = decode_events(start);
+                assert_eq!(events.len(), 1);
+                assert!(is_update_of(&events[0], Id::U64(1)));
+                // Collection wide changes update every token.
+                let start = recorded_events().count();
+                assert_eq!(token.set_base_uri(String::from("ipfs://base/")), Ok(()));
+                assert_eq!(token.set_uri_suffix(String::from("")), Ok(()));
+                assert_eq!(token.set_on_chain_metadata(true), Ok(()));
+                assert_eq!(token.set_pre_reveal_uri(Some(String::from("ipfs://hidden"))), Ok(()));
+                assert_eq!(token.reveal(String::from("ipfs://revealed/"), 7), Ok(()));
+                let events = decode_events(start);
+                assert_eq!(events.len(), 5);
+                assert!(events.iter().all(is_batch_update));
+            }
+
+            #[ink::test]
+            fn canonical_ids_work() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                // By default only the minted variant refers to the token.
+                assert_eq!(token.owner_of(Id::U8(1)), None);
+                assert_eq!(
+                    token.set_multiple_attributes(Id::U8(1), vec![(String::from("name"), String::from("Token 1"))]),
+                    Err(PSP34Error::InvalidInput)
+                );
+                // Only the owner can enable canonical ids.
+                set_caller(accounts.bob);
+                assert_eq!(token.set_canonical_ids(true), Err(PSP34Error::CallerIsNotOwner));
+                set_caller(accounts.alice);
+                assert_eq!(token.set_canonical_ids(true), Ok(()));
+                assert!(token.is_canonical_ids());
+                assert_eq!(token.owner_of(Id::U8(1)), Some(accounts.alice));
+                assert_eq!(token.owner_of(Id::U128(1)), Some(accounts.alice));
+                assert_eq!(
+                    token.set_multiple_attributes(Id::U16(1), vec![(String::from("name"), String::from("Token 1"))]),
+                    Ok(())
+                );
+                assert_eq!(token.get_attribute(Id::U64(1), b"name".to_vec()), Some(b"Token 1".to_vec()));
+                assert_eq!(token.transfer(accounts.bob, Id::U32(1), vec![]), Ok(()));
+                assert_eq!(token.owners_of(vec![Id::U8(1), Id::U64(1)]), vec![Some(accounts.bob); 2]);
+                // Ids which are not integers stay as they are.
+                assert_eq!(token.owner_of(Id::Bytes(vec![1])), None);
+            }
+
+            #[ink::test]
+            fn id_conversions_work() {
+                assert_eq!(u128::try_from(Id::U8(7)), Ok(7));
+                assert_eq!(u128::try_from(Id::Bytes([0u8; 16].to_vec())), Ok(0));
+                assert_eq!(u128::try_from(Id::Bytes(vec![1, 2])), Err(PSP34Error::InvalidInput));
+                assert_eq!(Id::U128(1).canonical(), Id::U64(1));
+                assert_eq!(Id::U128(u128::MAX).canonical(), Id::U128(u128::MAX));
+                for id in [Id::U8(1), Id::U16(2), Id::U32(3), Id::U64(4), Id::U128(5), Id::Bytes(vec![0x0a, 0xff])] {
+                    assert_eq!(id.to_string().parse::<Id>(), Ok(id));
+                }
+                assert_eq!(Id::Bytes(vec![0x0a, 0xff]).to_string(), "bytes:0x0aff");
+                assert_eq!("42".parse::<Id>(), Ok(Id::U64(42)));
+                assert_eq!("u8:256".parse::<Id>(), Err(PSP34Error::InvalidInput));
+                assert_eq!("bytes:0xabc".parse::<Id>(), Err(PSP34Error::InvalidInput));
+            }
+
+            #[ink::test]
+            fn rental_works() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert_eq!(token.mint(), Ok(()));
+                set_block_timestamp::<E>(1000);
+                // Only the owner or an approved operator can set the user.
+                set_caller(accounts.bob);
+                assert_eq!(token.set_user(Id::U64(1), Some(accounts.bob), 2000), Err(PSP34Error::NotApproved));
+                set_caller(accounts.alice);
+                assert_eq!(token.set_user(Id::U64(1), Some(accounts.bob), 1000), Err(PSP34Error::InvalidInput));
+                let start = recorded_events().count();
+                assert_eq!(token.set_user(Id::U64(1), Some(accounts.bob), 2000), Ok(()));
+                let events = decode_events(start);
+                assert!(matches!(
+                    &events[0],
+                    Event::UpdateUser(UpdateUser { id, user, expires })
+                        if *id == Id::U64(1) && *user == Some(accounts.bob) && *expires == 2000
+                ));
+                assert_eq!(token.user_of(Id::U64(1)), Some(accounts.bob));
+                assert_eq!(token.user_expires(Id::U64(1)), 2000);
+                // The user does not own the token.
+                assert_eq!(token.owner_of(Id::U64(1)), Some(accounts.alice));
+                // The rental ends at its expiry.
+                set_block_timestamp::<E>(2000);
+                assert_eq!(token.user_of(Id::U64(1)), None);
+                // An approved operator can rent the token out again.
+                assert_eq!(token.approve(accounts.charlie, Some(Id::U64(1)), true), Ok(()));
+                set_caller(accounts.charlie);
+                assert_eq!(token.set_user(Id::U64(1), Some(accounts.eve), 3000), Ok(()));
+                assert_eq!(token.user_of(Id::U64(1)), Some(accounts.eve));
+                // The user is cleared when the token is transferred.
+                let start = recorded_events().count();
+                assert_eq!(token.transfer(accounts.django, Id::U64(1), vec![]), Ok(()));
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                assert!(matches!(
+                    &events[0],
+                    Event::UpdateUser(UpdateUser { user: None, expires: 0, .. })
+                ));
+                assert_transfer(&events[1], accounts.alice, accounts.django, Id::U64(1));
+                assert_eq!(token.user_of(Id::U64(1)), None);
+                assert_eq!(token.user_expires(Id::U64(1)), 0);
+            }
+
+            #[ink::test]
+            fn recover_fails_for_regular_collection() {
+                let accounts = default_accounts::<E>();
+                // Create a new contract instance.
+                let mut token = $constructor();
+                assert!(!token.is_soulbound());
+                assert_eq!(token.mint(), Ok(()));
+                assert_eq!(token.recover(Id::U64(1), accounts.charlie), Err(PSP34Error::NonTransferable));
             }
         }
     };